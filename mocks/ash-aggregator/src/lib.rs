@@ -0,0 +1,57 @@
+#![no_std]
+
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, ManagedVecItem)]
+pub struct TokenAmount<M: ManagedTypeApi> {
+    pub token: TokenIdentifier<M>,
+    pub amount: BigUint<M>,
+}
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, ManagedVecItem)]
+pub struct AggregatorStep<M: ManagedTypeApi> {
+    pub token_in: TokenIdentifier<M>,
+    pub token_out: TokenIdentifier<M>,
+    pub amount_in: BigUint<M>,
+    pub pool_address: ManagedAddress<M>,
+    pub function_name: ManagedBuffer<M>,
+    pub arguments: ManagedVec<M, ManagedBuffer<M>>,
+}
+
+/// Mock of the AshSwap Aggregator used by the tests
+/// The whole input is kept and the configured output amount of the last limit token is sent back,
+/// without checking the limits so the caller has to check them
+#[multiversx_sc::contract]
+pub trait AshAggregatorMock {
+    #[init]
+    fn init(&self, output_amount: BigUint) {
+        self.output_amount().set(&output_amount);
+    }
+
+    #[payable("*")]
+    #[endpoint]
+    fn aggregate(
+        &self,
+        _steps: ManagedVec<AggregatorStep<Self::Api>>,
+        limits: MultiValueEncoded<TokenAmount<Self::Api>>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        let limits = limits.to_vec();
+        let output_token = limits.get(limits.len() - 1).token;
+        let output_amount = self.output_amount().get();
+
+        let mut result_payments = ManagedVec::new();
+        if output_amount > 0 {
+            let caller = self.blockchain().get_caller();
+            self.send()
+                .direct_esdt(&caller, &output_token, 0, &output_amount);
+            result_payments.push(EsdtTokenPayment::new(output_token, 0, output_amount));
+        }
+
+        result_payments
+    }
+
+    #[view(getOutputAmount)]
+    #[storage_mapper("outputAmount")]
+    fn output_amount(&self) -> SingleValueMapper<BigUint>;
+}
@@ -0,0 +1,62 @@
+#![no_std]
+
+multiversx_sc::imports!();
+
+/// Mock of a staking contract used by the claim and call tests
+/// A part of the deposit can be refunded and a receipt token is sent for the rest of it (if set)
+/// The payment can also be forwarded to another endpoint (e.g. to create a stream with it)
+#[multiversx_sc::contract]
+pub trait CallReceiverMock {
+    #[init]
+    fn init(&self, receipt_token: TokenIdentifier) {
+        self.receipt_token().set(&receipt_token);
+    }
+
+    #[payable("*")]
+    #[endpoint]
+    fn deposit(&self, refund_amount: BigUint) {
+        let payment = self.call_value().egld_or_single_esdt();
+        require!(refund_amount <= payment.amount, "Refund amount too big");
+
+        let caller = self.blockchain().get_caller();
+        if refund_amount > 0 {
+            self.send().direct(
+                &caller,
+                &payment.token_identifier,
+                payment.token_nonce,
+                &refund_amount,
+            );
+        }
+
+        let deposit_amount = &payment.amount - &refund_amount;
+        let receipt_token_mapper = self.receipt_token();
+        if !receipt_token_mapper.is_empty() && deposit_amount > 0 {
+            self.send()
+                .direct_esdt(&caller, &receipt_token_mapper.get(), 0, &deposit_amount);
+        }
+    }
+
+    #[payable("*")]
+    #[endpoint]
+    fn forward(
+        &self,
+        target: ManagedAddress,
+        endpoint: ManagedBuffer,
+        args: MultiValueEncoded<ManagedBuffer>,
+    ) {
+        let payment = self.call_value().egld_or_single_esdt();
+
+        let mut contract_call = self
+            .send()
+            .contract_call::<IgnoreValue>(target, endpoint)
+            .with_egld_or_single_esdt_transfer(payment);
+        for arg in args {
+            contract_call = contract_call.argument(&arg);
+        }
+        contract_call.execute_on_dest_context::<IgnoreValue>();
+    }
+
+    #[view(getReceiptToken)]
+    #[storage_mapper("receiptToken")]
+    fn receipt_token(&self) -> SingleValueMapper<TokenIdentifier>;
+}
@@ -0,0 +1,47 @@
+#![no_std]
+
+multiversx_sc::imports!();
+
+/// Mock of the wrap EGLD contract used by the tests
+/// EGLD and WEGLD are exchanged one to one from the balances of the contract
+#[multiversx_sc::contract]
+pub trait WrapEgldMock {
+    #[init]
+    fn init(&self, wrapped_egld_token_id: TokenIdentifier) {
+        self.wrapped_egld_token_id().set(&wrapped_egld_token_id);
+    }
+
+    #[payable("EGLD")]
+    #[endpoint(wrapEgld)]
+    fn wrap_egld(&self) -> EsdtTokenPayment {
+        let amount = self.call_value().egld_value().clone_value();
+        let payment = EsdtTokenPayment::new(self.wrapped_egld_token_id().get(), 0, amount);
+
+        let caller = self.blockchain().get_caller();
+        self.send().direct_esdt(
+            &caller,
+            &payment.token_identifier,
+            payment.token_nonce,
+            &payment.amount,
+        );
+
+        payment
+    }
+
+    #[payable("*")]
+    #[endpoint(unwrapEgld)]
+    fn unwrap_egld(&self) {
+        let payment = self.call_value().single_esdt();
+        require!(
+            payment.token_identifier == self.wrapped_egld_token_id().get(),
+            "Wrong esdt token"
+        );
+
+        let caller = self.blockchain().get_caller();
+        self.send().direct_egld(&caller, &payment.amount);
+    }
+
+    #[view(getWrappedEgldTokenId)]
+    #[storage_mapper("wrappedEgldTokenId")]
+    fn wrapped_egld_token_id(&self) -> SingleValueMapper<TokenIdentifier>;
+}
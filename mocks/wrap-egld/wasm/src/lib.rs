@@ -0,0 +1,31 @@
+// Code generated by the multiversx-sc build system. DO NOT EDIT.
+
+////////////////////////////////////////////////////
+////////////////// AUTO-GENERATED //////////////////
+////////////////////////////////////////////////////
+
+// Init:                                 1
+// Endpoints:                            3
+// Async Callback (empty):               1
+// Total number of exported functions:   5
+
+#![no_std]
+
+// Configuration that works with rustc < 1.73.0.
+// TODO: Recommended rustc version: 1.73.0 or newer.
+#![feature(lang_items)]
+
+multiversx_sc_wasm_adapter::allocator!();
+multiversx_sc_wasm_adapter::panic_handler!();
+
+multiversx_sc_wasm_adapter::endpoints! {
+    wrap_egld_mock
+    (
+        init => init
+        wrapEgld => wrap_egld
+        unwrapEgld => unwrap_egld
+        getWrappedEgldTokenId => wrapped_egld_token_id
+    )
+}
+
+multiversx_sc_wasm_adapter::async_callback_empty! {}
@@ -0,0 +1,198 @@
+use crate::{
+    errors::{
+        ERR_ACCEPTANCE_DEADLINE_NOT_PASSED, ERR_ACCEPTANCE_DEADLINE_PASSED, ERR_INVALID_SECRET,
+        ERR_NOT_AWAITING_ACCEPTANCE, ERR_RECLAIM_ONLY_SENDER, ERR_REDEEM_NOT_COMMITTED,
+        ERR_STREAM_HASH_LOCKED, ERR_STREAM_NOT_HASH_LOCKED, ERR_STREAM_TO_CALLER,
+    },
+    storage::{Status, StreamRole},
+};
+
+multiversx_sc::imports!();
+
+// Redeem commitments cleared per call, so spamming commitments can't block a redeem or a reclaim
+const MAX_CLEARED_REDEEM_COMMITMENTS: usize = 20;
+
+#[multiversx_sc::module]
+pub trait AcceptStreamModule:
+    crate::storage::StorageModule
+    + crate::events::EventsModule
+    + crate::claim::ClaimModule
+    + crate::status::StatusModule
+    + crate::stream_nft::StreamNftModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+{
+    /// This endpoint can be used by the recipient of a stream created with an acceptance deadline to accept it
+    /// The stream NFT must be sent before the deadline and it is sent back to the recipient
+    #[payable("*")]
+    #[endpoint(acceptStream)]
+    fn accept_stream(&self, stream_id: u64) {
+        let (_, mut stream) =
+            self.require_valid_stream_nft(stream_id, OptionalValue::Some(StreamRole::Recipient));
+
+        require!(
+            self.status_of(stream_id) == Status::AwaitingAcceptance,
+            ERR_NOT_AWAITING_ACCEPTANCE
+        );
+
+        let current_time = self.blockchain().get_block_timestamp();
+        let acceptance_deadline = stream.acceptance_deadline.unwrap();
+        require!(
+            current_time <= acceptance_deadline,
+            ERR_ACCEPTANCE_DEADLINE_PASSED
+        );
+
+        stream.acceptance_deadline = None;
+        self.save_stream(stream_id, &stream);
+
+        let caller = self.blockchain().get_caller();
+        self.send().direct_esdt(
+            &caller,
+            self.stream_nft_token().get_token_id_ref(),
+            stream.nft_nonce,
+            &BigUint::from(1u32),
+        );
+
+        self.accept_stream_event(stream_id, &caller);
+    }
+
+    /// This endpoint can be used to commit to the redeem of a hash locked stream before revealing its secret
+    /// The commitment is the sha256 hash of the secret followed by the address of the caller,
+    /// so a secret seen in a pending redeem can't be used by another address
+    #[endpoint(commitRedeem)]
+    fn commit_redeem(&self, stream_id: u64, commitment: ManagedByteArray<Self::Api, 32>) {
+        require!(
+            !self.stream_secret_hash(stream_id).is_empty(),
+            ERR_STREAM_NOT_HASH_LOCKED
+        );
+
+        let stream = self.get_stream(stream_id);
+        let current_time = self.blockchain().get_block_timestamp();
+        let expiry = stream.acceptance_deadline.unwrap();
+        require!(current_time <= expiry, ERR_ACCEPTANCE_DEADLINE_PASSED);
+
+        let commitment_mapper = self.redeem_commitment(stream_id, &commitment);
+        if commitment_mapper.is_empty() {
+            commitment_mapper.set(self.blockchain().get_block_nonce());
+            self.redeem_commitments(stream_id)
+                .insert(commitment.clone());
+        }
+
+        let caller = self.blockchain().get_caller();
+        self.commit_redeem_event(stream_id, &caller, &commitment);
+    }
+
+    /// This endpoint can be used to redeem a hash locked stream by revealing its secret before the expiry
+    /// The redeem must have been committed by the caller in a previous block (see commitRedeem)
+    /// The caller becomes the recipient of the stream and receives the stream NFT
+    #[endpoint(redeemStream)]
+    fn redeem_stream(&self, stream_id: u64, secret: ManagedBuffer) {
+        let secret_hash_mapper = self.stream_secret_hash(stream_id);
+        require!(!secret_hash_mapper.is_empty(), ERR_STREAM_NOT_HASH_LOCKED);
+
+        let mut stream = self.get_stream(stream_id);
+
+        let caller = self.blockchain().get_caller();
+        require!(caller != stream.sender, ERR_STREAM_TO_CALLER);
+
+        let current_time = self.blockchain().get_block_timestamp();
+        let expiry = stream.acceptance_deadline.unwrap();
+        require!(current_time <= expiry, ERR_ACCEPTANCE_DEADLINE_PASSED);
+
+        let secret_sha256 = self.crypto().sha256(&secret);
+        require!(
+            secret_sha256 == secret_hash_mapper.get(),
+            ERR_INVALID_SECRET
+        );
+
+        let mut commitment_data = secret.clone();
+        commitment_data.append(caller.as_managed_buffer());
+        let commitment = self.crypto().sha256(&commitment_data);
+        let commitment_mapper = self.redeem_commitment(stream_id, &commitment);
+        require!(
+            !commitment_mapper.is_empty()
+                && commitment_mapper.get() < self.blockchain().get_block_nonce(),
+            ERR_REDEEM_NOT_COMMITTED
+        );
+
+        secret_hash_mapper.clear();
+        self.clear_redeem_commitments_batch(stream_id);
+        stream.acceptance_deadline = None;
+        self.save_stream(stream_id, &stream);
+
+        self.send().direct_esdt(
+            &caller,
+            self.stream_nft_token().get_token_id_ref(),
+            stream.nft_nonce,
+            &BigUint::from(1u32),
+        );
+
+        self.redeem_stream_event(stream_id, &caller);
+    }
+
+    /// This endpoint can be used by the sender to get back the full deposit of a stream
+    /// that was not accepted (or redeemed) before the acceptance deadline (or expiry)
+    #[endpoint(reclaimStream)]
+    fn reclaim_stream(&self, stream_id: u64) {
+        let stream = self.get_stream(stream_id);
+
+        let caller = self.blockchain().get_caller();
+        require!(caller == stream.sender, ERR_RECLAIM_ONLY_SENDER);
+
+        let status = self.status_of(stream_id);
+        require!(
+            status == Status::AwaitingAcceptance || status == Status::AwaitingRedeem,
+            ERR_NOT_AWAITING_ACCEPTANCE
+        );
+
+        let current_time = self.blockchain().get_block_timestamp();
+        let acceptance_deadline = stream.acceptance_deadline.unwrap();
+        require!(
+            current_time > acceptance_deadline,
+            ERR_ACCEPTANCE_DEADLINE_NOT_PASSED
+        );
+
+        self.send().direct(
+            &stream.sender,
+            &stream.payment_token,
+            stream.payment_nonce,
+            &stream.deposit,
+        );
+
+        self.reclaim_stream_event(stream_id, &stream.sender, &stream.deposit);
+
+        // The NFT of a hash locked stream is held by the contract so it can be burned,
+        // otherwise the stream NFT is held by the recipient
+        let secret_hash_mapper = self.stream_secret_hash(stream_id);
+        let is_hash_locked = !secret_hash_mapper.is_empty();
+        secret_hash_mapper.clear();
+        self.clear_redeem_commitments_batch(stream_id);
+
+        self.remove_stream(stream_id, is_hash_locked);
+    }
+
+    /// This endpoint can be used by anyone to clear the redeem commitments left
+    /// after a hash locked stream was redeemed or reclaimed
+    #[endpoint(clearRedeemCommitments)]
+    fn clear_redeem_commitments(&self, stream_id: u64) {
+        require!(
+            self.stream_secret_hash(stream_id).is_empty(),
+            ERR_STREAM_HASH_LOCKED
+        );
+
+        self.clear_redeem_commitments_batch(stream_id);
+    }
+
+    /// Clears up to MAX_CLEARED_REDEEM_COMMITMENTS redeem commitments of the stream
+    fn clear_redeem_commitments_batch(&self, stream_id: u64) {
+        let mut commitments_mapper = self.redeem_commitments(stream_id);
+        for _ in 0..MAX_CLEARED_REDEEM_COMMITMENTS {
+            if commitments_mapper.is_empty() {
+                break;
+            }
+
+            let commitment = commitments_mapper.get_by_index(1);
+            commitments_mapper.swap_remove(&commitment);
+            self.redeem_commitment(stream_id, &commitment).clear();
+        }
+    }
+}
@@ -4,7 +4,7 @@ use crate::{
     errors::{
         ERR_CANCEL_ONLY_SENDER, ERR_CANT_CANCEL, ERR_STREAM_IS_NOT_CANCELLED, ERR_ZERO_CLAIM,
     },
-    storage::{BalancesAfterCancel, StreamAttributes, StreamRole},
+    storage::{BalancesAfterCancel, StreamRole},
 };
 
 #[multiversx_sc::module]
@@ -38,7 +38,7 @@ pub trait CancelStreamModule:
             recipient_balance: recipient_balance.clone(),
         });
 
-        self.stream_by_id(stream_id).set(stream.clone());
+        self.save_stream(stream_id, &stream);
 
         let with_claim: bool = (&_with_claim.into_option()).unwrap_or(true);
         if with_claim {
@@ -48,9 +48,7 @@ pub trait CancelStreamModule:
         let caller = self.blockchain().get_caller();
 
         if !with_claim && role == StreamRole::Recipient {
-            let mut nft_attributes: StreamAttributes<Self::Api> = self
-                .stream_nft_token()
-                .get_token_attributes(stream.nft_nonce);
+            let mut nft_attributes = self.get_stream_nft_attributes(&stream);
             nft_attributes.remaining_balance = recipient_balance;
             nft_attributes.is_canceled = true;
             self.stream_nft_token()
@@ -69,6 +67,7 @@ pub trait CancelStreamModule:
 
     /// After a stream was cancelled, you can call this endpoint to claim the streamed tokens as a recipient or the remaining tokens as a sender
     /// This endpoint is especially helpful when the recipient/sender is a non-payable smart contract
+    /// The recipient tokens are sent to the claim destination set by the caller for this stream (if any)
     /// For convenience, this endpoint is automatically called by default from the cancel_stream endpoint (is not instructed otherwise by the "_with_claim" param)
     #[payable("*")]
     #[endpoint(claimFromStreamAfterCancel)]
@@ -94,8 +93,9 @@ pub trait CancelStreamModule:
             balances_after_cancel.sender_balance = BigUint::zero();
         } else {
             require!(balances_after_cancel.recipient_balance > 0, ERR_ZERO_CLAIM);
+            let destination = self.get_claim_destination(stream_id, &caller);
             self.send().direct(
-                &caller,
+                &destination,
                 &stream.payment_token,
                 stream.payment_nonce,
                 &balances_after_cancel.recipient_balance,
@@ -105,6 +105,7 @@ pub trait CancelStreamModule:
                 stream_id,
                 &balances_after_cancel.recipient_balance,
                 &caller,
+                &destination,
             );
             balances_after_cancel.recipient_balance = BigUint::zero();
 
@@ -116,7 +117,7 @@ pub trait CancelStreamModule:
             self.remove_stream(stream_id, false);
         } else {
             stream.balances_after_cancel = Some(balances_after_cancel);
-            self.stream_by_id(stream_id).set(stream);
+            self.save_stream(stream_id, &stream);
         }
     }
 
@@ -135,7 +136,7 @@ pub trait CancelStreamModule:
 
         stream.can_cancel = false;
 
-        self.stream_by_id(stream_id).set(stream);
+        self.save_stream(stream_id, &stream);
 
         self.renounce_cancel_stream_event(stream_id);
     }
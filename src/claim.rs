@@ -2,39 +2,17 @@ multiversx_sc::imports!();
 
 use crate::{
     errors::{
-        ERR_ASH_INVALID_LIMITS_STEPS_LENGTH, ERR_ASH_INVALID_STEPS_LENGTH, ERR_CANT_CLAIM,
-        ERR_ZERO_CLAIM, ERR_ZERO_INVALID_CLAIM_AMOUNT,
+        ERR_ASYNC_SWAP_OUTPUT_LIMIT, ERR_CALL_INVALID_RETURN_TOKEN, ERR_CALL_NOT_WHITELISTED,
+        ERR_CANT_CLAIM, ERR_CLAIM_DESTINATION_SC, ERR_INVALID_NFT_TOKEN, ERR_INVALID_ROLE,
+        ERR_NO_STREAM_NFTS, ERR_ZERO_CLAIM, ERR_ZERO_INVALID_CLAIM_AMOUNT,
+    },
+    storage::{
+        AggregatorStep, ClaimDestination, Segment, Status, Stream, StreamRole, SwapAdapter,
+        TokenAmount,
     },
-    storage::{AggregatorStep, Segment, Status, StreamAttributes, StreamRole, TokenAmount},
 };
 
-mod ash_aggregator {
-    use crate::storage::{AggregatorStep, TokenAmount};
-
-    multiversx_sc::imports!();
-
-    #[multiversx_sc::proxy]
-    pub trait AggregatorContract {
-        #[payable("*")]
-        #[endpoint]
-        fn aggregate(
-            &self,
-            steps: ManagedVec<AggregatorStep<Self::Api>>,
-            limits: MultiValueEncoded<TokenAmount<Self::Api>>,
-        ) -> ManagedVec<EsdtTokenPayment>;
-    }
-}
-
-mod wrap_egld {
-    multiversx_sc::imports!();
-
-    #[multiversx_sc::proxy]
-    pub trait WrapEgldContract {
-        #[payable("EGLD")]
-        #[endpoint(wrapEgld)]
-        fn wrap_egld(&self) -> EsdtTokenPayment;
-    }
-}
+const EXPONENT_PRECISION: u64 = 1_000_000_000_000_000_000;
 
 #[multiversx_sc::module]
 pub trait ClaimModule:
@@ -42,14 +20,9 @@ pub trait ClaimModule:
     + crate::events::EventsModule
     + crate::status::StatusModule
     + crate::stream_nft::StreamNftModule
+    + crate::swap::SwapModule
     + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
 {
-    #[proxy]
-    fn ash_aggregator_proxy(&self, sc_address: ManagedAddress) -> ash_aggregator::Proxy<Self::Api>;
-
-    #[proxy]
-    fn wrap_egld_proxy(&self, sc_address: ManagedAddress) -> wrap_egld::Proxy<Self::Api>;
-
     /// Compute the streamed amount from a specific stream segment
     fn compute_segment_value(
         &self,
@@ -67,16 +40,51 @@ pub trait ClaimModule:
             return segment.amount;
         }
 
-        let numerator = BigUint::from(current_time - segment_start_time)
+        if segment.exponent_denominator == 1 {
+            let numerator = BigUint::from(current_time - segment_start_time)
+                .pow(segment.exponent)
+                .mul(segment.amount);
+            let denominator = BigUint::from(segment.duration).pow(segment.exponent);
+
+            return numerator.div(denominator);
+        }
+
+        // For rational exponents the elapsed ratio is raised to the exponent numerator in fixed point
+        // and the exponent denominator root is extracted from it
+        // value = amount * root(ratio ^ numerator * PRECISION ^ denominator, denominator) / PRECISION
+        let precision = BigUint::from(EXPONENT_PRECISION);
+        let scaled_ratio = BigUint::from(current_time - segment_start_time)
             .pow(segment.exponent)
-            .mul(segment.amount);
-        let denominator = BigUint::from(segment.duration).pow(segment.exponent);
+            .mul(precision.pow(segment.exponent_denominator))
+            .div(BigUint::from(segment.duration).pow(segment.exponent));
+        let scaled_value = self.nth_root(&scaled_ratio, segment.exponent_denominator, &precision);
 
-        numerator.div(denominator)
+        scaled_value.mul(segment.amount).div(precision)
+    }
+
+    /// Integer n-th root (rounded down) using Newton's method
+    /// The initial guess must be bigger than or equal to the result
+    fn nth_root(&self, value: &BigUint, n: u32, initial_guess: &BigUint) -> BigUint {
+        if *value == 0 {
+            return BigUint::zero();
+        }
+
+        let big_n = BigUint::from(n);
+        let big_n_minus_one = BigUint::from(n - 1);
+
+        let mut root = initial_guess.clone();
+        loop {
+            let next_root = (&root * &big_n_minus_one + value / &root.pow(n - 1)) / &big_n;
+            if next_root >= root {
+                return root;
+            }
+            root = next_root;
+        }
     }
 
     ///
     /// Calculates the entire streamed amount until the current time
+    /// The cliff amount is included once the cliff has passed
     /// |************|--|
     /// S            C  E
     /// S = start time
@@ -86,6 +94,10 @@ pub trait ClaimModule:
     #[view(streamedAmount)]
     fn streamed_amount(&self, stream_id: u64) -> BigUint {
         let stream = self.get_stream(stream_id);
+        self.get_streamed_amount(&stream)
+    }
+
+    fn get_streamed_amount(&self, stream: &Stream<Self::Api>) -> BigUint {
         let current_time = self.blockchain().get_block_timestamp();
 
         if current_time < stream.start_time {
@@ -97,11 +109,12 @@ pub trait ClaimModule:
         }
 
         if current_time > stream.end_time {
-            return stream.deposit;
+            return stream.deposit.clone();
         }
 
+        // The cliff amount is released at once when the cliff ends
         let mut last_segment_end_time = stream.start_time;
-        let mut recipient_balance = BigUint::zero();
+        let mut recipient_balance = stream.cliff_amount.clone();
         for segment in &stream.segments {
             let segment_amount = self.compute_segment_value(last_segment_end_time, segment.clone());
 
@@ -113,7 +126,7 @@ pub trait ClaimModule:
             last_segment_end_time += segment.duration;
         }
 
-        recipient_balance.min(stream.deposit)
+        recipient_balance.min(stream.deposit.clone())
     }
 
     /// Calculates the recipient balance based on the amount stream so far and the already claimed amount
@@ -167,15 +180,49 @@ pub trait ClaimModule:
         return is_finalized;
     }
 
+    /// The claimed payment is returned and has to be sent to the destination by the caller of this function
     fn claim_from_stream_internal(
         &self,
         stream_id: u64,
+        destination: &ManagedAddress,
         amount_to_claim_opt: Option<BigUint>,
     ) -> EgldOrEsdtTokenPayment {
         // Validate the NFT and retrieve the associated stream
-        let (_, mut stream) =
+        let (_, stream) =
             self.require_valid_stream_nft(stream_id, OptionalValue::Some(StreamRole::Recipient));
+        let caller = self.blockchain().get_caller();
+
+        let (payment, is_removed) = self.claim_stream(
+            stream_id,
+            stream.clone(),
+            &caller,
+            destination,
+            amount_to_claim_opt,
+        );
 
+        if !is_removed {
+            self.send().direct_esdt(
+                &caller,
+                self.stream_nft_token().get_token_id_ref(),
+                stream.nft_nonce,
+                &BigUint::from(1u32),
+            );
+        }
+
+        payment
+    }
+
+    /// Claims from a stream whose NFT was sent by the caller
+    /// Returns the claimed payment and whether the stream was removed (its NFT is burned)
+    /// A stream is removed only when its whole deposit was claimed after its end time
+    fn claim_stream(
+        &self,
+        stream_id: u64,
+        mut stream: Stream<Self::Api>,
+        caller: &ManagedAddress,
+        destination: &ManagedAddress,
+        amount_to_claim_opt: Option<BigUint>,
+    ) -> (EgldOrEsdtTokenPayment, bool) {
         // Check the stream status
         let current_status = self.status_of(stream_id);
         let is_warm = self.is_warm(stream_id);
@@ -185,54 +232,293 @@ pub trait ClaimModule:
         let amount = self.recipient_balance(stream_id);
         require!(amount > 0, ERR_ZERO_CLAIM);
         let amount_to_claim = amount_to_claim_opt.unwrap_or(amount.clone());
-        require!(amount_to_claim <= amount, ERR_ZERO_INVALID_CLAIM_AMOUNT);
+        require!(
+            amount_to_claim > 0 && amount_to_claim <= amount,
+            ERR_ZERO_INVALID_CLAIM_AMOUNT
+        );
 
-        let is_finalized = self.is_stream_finalized(stream_id);
-        let caller = self.blockchain().get_caller();
+        let is_fully_claimed = self.is_stream_finalized(stream_id) && amount_to_claim == amount;
 
-        if is_finalized {
+        if is_fully_claimed {
             self.remove_stream(stream_id, true);
         } else {
             stream.claimed_amount += &amount_to_claim;
-            self.stream_by_id(stream_id).set(&stream);
+            self.save_stream(stream_id, &stream);
 
-            let mut nft_attributes: StreamAttributes<Self::Api> = self
-                .stream_nft_token()
-                .get_token_attributes(stream.nft_nonce);
-            nft_attributes.remaining_balance -= &amount_to_claim;
+            // Attributes are rebuilt from the stream so changes made while the NFT was not
+            // held by the contract (e.g. top ups) are reflected as well
+            let nft_attributes = self.get_stream_nft_attributes(&stream);
             self.stream_nft_token()
                 .nft_update_attributes(stream.nft_nonce, &nft_attributes);
-
-            self.send().direct_esdt(
-                &caller,
-                self.stream_nft_token().get_token_id_ref(),
-                stream.nft_nonce,
-                &BigUint::from(1u32),
-            );
         }
 
-        self.claim_from_stream_event(stream_id, &amount, &caller);
+        self.claim_from_stream_event(stream_id, &amount_to_claim, caller, destination);
 
-        EgldOrEsdtTokenPayment::new(stream.payment_token, stream.payment_nonce, amount_to_claim)
+        let payment = EgldOrEsdtTokenPayment::new(
+            stream.payment_token,
+            stream.payment_nonce,
+            amount_to_claim,
+        );
+
+        (payment, is_fully_claimed)
     }
 
     /// This endpoint can be used by the recipient of the stream to claim the stream amount of tokens
+    /// The claimed tokens are sent to the claim destination set by the caller for this stream (if any)
     #[payable("*")]
     #[endpoint(claimFromStream)]
     fn claim_from_stream(&self, stream_id: u64) {
-        let payment = self.claim_from_stream_internal(stream_id, None);
+        let caller = self.blockchain().get_caller();
+        let destination = self.get_claim_destination(stream_id, &caller);
 
+        self.claim_from_stream_to_destination(stream_id, &destination, None);
+    }
+
+    /// This endpoint can be used by the recipient of the stream to claim only a part of the recipient balance
+    /// The stream NFT is sent back to the caller, even if the stream has ended, as long as there are tokens left to claim
+    #[payable("*")]
+    #[endpoint(claimFromStreamAmount)]
+    fn claim_from_stream_amount(&self, stream_id: u64, amount: BigUint) {
         let caller = self.blockchain().get_caller();
+        let destination = self.get_claim_destination(stream_id, &caller);
+
+        self.claim_from_stream_to_destination(stream_id, &destination, Some(amount));
+    }
+
+    /// This endpoint can be used by the recipient of the stream to claim the streamed tokens to another address
+    /// The stream NFT is sent back to the caller
+    #[payable("*")]
+    #[endpoint(claimFromStreamTo)]
+    fn claim_from_stream_to(&self, stream_id: u64, destination: ManagedAddress) {
+        self.claim_from_stream_to_destination(stream_id, &destination, None);
+    }
+
+    fn claim_from_stream_to_destination(
+        &self,
+        stream_id: u64,
+        destination: &ManagedAddress,
+        amount_to_claim_opt: Option<BigUint>,
+    ) {
+        self.require_valid_claim_destination(destination);
+
+        let payment = self.claim_from_stream_internal(stream_id, destination, amount_to_claim_opt);
+
         // Send claimed tokens
         self.send().direct(
-            &caller,
+            destination,
             &payment.token_identifier,
             payment.token_nonce,
             &payment.amount,
         );
     }
 
+    /// This endpoint can be used to claim from a stream straight into a contract (e.g. staking or lending)
+    /// The claimed tokens are sent with a synchronous call to an endpoint whitelisted by the owner,
+    /// so the target must be in the same shard and the whole claim is reverted if the call fails
+    /// The EGLD, claimed tokens and return tokens of the endpoint sent back by the target are forwarded to the caller,
+    /// any other token sent back reverts the claim
+    /// Only the payments sent back to the contract are forwarded (back transfers), so tokens deposited
+    /// by the target during the call (e.g. a new stream) stay with the contract
+    #[payable("*")]
+    #[endpoint(claimFromStreamAndCall)]
+    fn claim_from_stream_and_call(
+        &self,
+        stream_id: u64,
+        target: ManagedAddress,
+        endpoint: ManagedBuffer,
+        args: MultiValueEncoded<ManagedBuffer>,
+    ) {
+        require!(
+            self.whitelisted_call_endpoints(&target).contains(&endpoint),
+            ERR_CALL_NOT_WHITELISTED
+        );
+
+        let payment = self.claim_from_stream_internal(stream_id, &target, None);
+        let claimed_token = payment.token_identifier.clone();
+        let claimed_nonce = payment.token_nonce;
+        let return_tokens_mapper = self.call_return_tokens(&target, &endpoint);
+
+        let mut contract_call = self
+            .send()
+            .contract_call::<IgnoreValue>(target, endpoint)
+            .with_egld_or_single_esdt_transfer(payment);
+        for arg in args {
+            contract_call = contract_call.argument(&arg);
+        }
+        let (_, back_transfers) =
+            contract_call.execute_on_dest_context_with_back_transfers::<IgnoreValue>();
+
+        for back_transfer in back_transfers.esdt_payments.iter() {
+            let is_claimed_token = claimed_token == back_transfer.token_identifier
+                && claimed_nonce == back_transfer.token_nonce;
+            require!(
+                is_claimed_token || return_tokens_mapper.contains(&back_transfer.token_identifier),
+                ERR_CALL_INVALID_RETURN_TOKEN
+            );
+        }
+
+        let caller = self.blockchain().get_caller();
+        if back_transfers.total_egld_amount > 0 {
+            self.send()
+                .direct_egld(&caller, &back_transfers.total_egld_amount);
+        }
+        if !back_transfers.esdt_payments.is_empty() {
+            self.send()
+                .direct_multi(&caller, &back_transfers.esdt_payments);
+        }
+    }
+
+    /// This endpoint can be used by the recipient of the stream to set the address where the claimed tokens are sent
+    /// The destination is bound to the caller, so it is not used anymore if the stream NFT is transferred
+    /// Calling it without a destination removes the current one
+    #[payable("*")]
+    #[endpoint(setClaimDestination)]
+    fn set_claim_destination(
+        &self,
+        stream_id: u64,
+        destination_opt: OptionalValue<ManagedAddress>,
+    ) {
+        let (_, stream) =
+            self.require_valid_stream_nft(stream_id, OptionalValue::Some(StreamRole::Recipient));
+        let caller = self.blockchain().get_caller();
+
+        let destination = destination_opt.into_option();
+        match &destination {
+            Some(destination) => {
+                self.require_valid_claim_destination(destination);
+                self.claim_destination(stream_id).set(ClaimDestination {
+                    holder: caller.clone(),
+                    destination: destination.clone(),
+                });
+            }
+            None => self.claim_destination(stream_id).clear(),
+        }
+
+        self.send().direct_esdt(
+            &caller,
+            self.stream_nft_token().get_token_id_ref(),
+            stream.nft_nonce,
+            &BigUint::from(1u32),
+        );
+
+        self.claim_destination_event(stream_id, &caller, &destination);
+    }
+
+    /// Returns the claim destination set by the holder for this stream or the holder itself
+    fn get_claim_destination(&self, stream_id: u64, holder: &ManagedAddress) -> ManagedAddress {
+        let claim_destination_mapper = self.claim_destination(stream_id);
+        if !claim_destination_mapper.is_empty() {
+            let claim_destination = claim_destination_mapper.get();
+            if &claim_destination.holder == holder {
+                return claim_destination.destination;
+            }
+        }
+
+        holder.clone()
+    }
+
+    fn require_valid_claim_destination(&self, destination: &ManagedAddress) {
+        require!(
+            destination != &self.blockchain().get_sc_address(),
+            ERR_CLAIM_DESTINATION_SC
+        );
+    }
+
+    /// This endpoint can be used by the recipient of multiple streams to claim from all of them at once
+    /// The stream NFTs are sent in a multi-ESDT transfer; streams with nothing to claim yet or that can't be claimed
+    /// (e.g. canceled or awaiting acceptance) are skipped and their NFTs are sent back
+    /// The NFTs of the streams that are still active are sent back and the claimed amounts are grouped by token,
+    /// everything being sent in a single multi-transfer (EGLD and payouts with a claim destination are sent separately)
+    #[payable("*")]
+    #[endpoint(claimFromStreams)]
+    fn claim_from_streams(&self) {
+        let stream_nfts = self.call_value().all_esdt_transfers().clone_value();
+        require!(!stream_nfts.is_empty(), ERR_NO_STREAM_NFTS);
+
+        let caller = self.blockchain().get_caller();
+        let stream_nft_token = self.stream_nft_token().get_token_id();
+
+        let mut egld_amount = BigUint::zero();
+        let mut transfers = ManagedVec::<Self::Api, EsdtTokenPayment<Self::Api>>::new();
+        for stream_nft in &stream_nfts {
+            require!(
+                stream_nft.token_identifier == stream_nft_token,
+                ERR_INVALID_NFT_TOKEN
+            );
+
+            let stream_id = self.get_stream_id_by_nft_nonce(stream_nft.token_nonce);
+            let stream = self.get_stream(stream_id);
+            require!(stream.nft_nonce == stream_nft.token_nonce, ERR_INVALID_ROLE);
+
+            // Streams that can't be claimed (e.g. canceled or awaiting acceptance) are skipped as well
+            let is_claimable =
+                self.is_warm(stream_id) || self.status_of(stream_id) == Status::Settled;
+            if !is_claimable || self.recipient_balance(stream_id) == 0 {
+                transfers.push(stream_nft);
+                continue;
+            }
+
+            let destination = self.get_claim_destination(stream_id, &caller);
+            let (payment, is_removed) =
+                self.claim_stream(stream_id, stream, &caller, &destination, None);
+            if !is_removed {
+                transfers.push(stream_nft);
+            }
+
+            // Payouts of streams with their own claim destination can't be grouped
+            if destination != caller {
+                self.send().direct(
+                    &destination,
+                    &payment.token_identifier,
+                    payment.token_nonce,
+                    &payment.amount,
+                );
+            } else if payment.token_identifier.is_egld() {
+                egld_amount += payment.amount;
+            } else {
+                self.add_grouped_payment(
+                    &mut transfers,
+                    EsdtTokenPayment::new(
+                        payment.token_identifier.unwrap_esdt(),
+                        payment.token_nonce,
+                        payment.amount,
+                    ),
+                );
+            }
+        }
+
+        if egld_amount > 0 {
+            self.send().direct_egld(&caller, &egld_amount);
+        }
+        if !transfers.is_empty() {
+            self.send().direct_multi(&caller, &transfers);
+        }
+    }
+
+    fn add_grouped_payment(
+        &self,
+        payments: &mut ManagedVec<EsdtTokenPayment<Self::Api>>,
+        payment: EsdtTokenPayment<Self::Api>,
+    ) {
+        let index_opt = payments.iter().position(|p| {
+            p.token_identifier == payment.token_identifier && p.token_nonce == payment.token_nonce
+        });
+
+        match index_opt {
+            Some(index) => {
+                let mut existing_payment = payments.get(index);
+                existing_payment.amount += payment.amount;
+                let _ = payments.set(index, &existing_payment);
+            }
+            None => payments.push(payment),
+        }
+    }
+
     /// This endpoint is using the AshSwap Aggregator to automatically swap the claimed tokens
+    /// The claimed amount defaults to the input amount of the swap route
+    /// If a bigger amount is claimed, the part that is not swapped is sent as it is
+    /// If unwrap_egld is set, the WEGLD resulting from the swap is sent as EGLD
+    #[allow_multiple_var_args]
     #[payable("*")]
     #[endpoint(claimFromStreamSwap)]
     fn claim_from_stream_swap(
@@ -240,45 +526,125 @@ pub trait ClaimModule:
         stream_id: u64,
         steps: ManagedVec<AggregatorStep<Self::Api>>,
         limits: ManagedVec<TokenAmount<Self::Api>>,
+        amount_opt: OptionalValue<BigUint>,
+        unwrap_egld_opt: OptionalValue<bool>,
+    ) {
+        let adapter = self.get_ash_aggregator_adapter();
+        self.claim_from_stream_swap_internal(
+            stream_id,
+            &adapter,
+            steps,
+            limits,
+            amount_opt.into_option(),
+            unwrap_egld_opt.into_option().unwrap_or(false),
+        );
+    }
+
+    /// Same as claimFromStreamSwap, but the swap is done using a swap adapter registered by the owner
+    #[allow_multiple_var_args]
+    #[payable("*")]
+    #[endpoint(claimFromStreamSwapWithAdapter)]
+    fn claim_from_stream_swap_with_adapter(
+        &self,
+        stream_id: u64,
+        adapter_id: u64,
+        steps: ManagedVec<AggregatorStep<Self::Api>>,
+        limits: ManagedVec<TokenAmount<Self::Api>>,
+        amount_opt: OptionalValue<BigUint>,
+        unwrap_egld_opt: OptionalValue<bool>,
+    ) {
+        let adapter = self.get_swap_adapter(adapter_id);
+        self.claim_from_stream_swap_internal(
+            stream_id,
+            &adapter,
+            steps,
+            limits,
+            amount_opt.into_option(),
+            unwrap_egld_opt.into_option().unwrap_or(false),
+        );
+    }
+
+    /// Same as claimFromStreamSwap, but the swap is done asynchronously so a failing route doesn't revert the claim
+    /// If the swap fails, the claimed tokens are sent unswapped to the destination and a swapFailed event is emitted
+    /// The swap is done by the swap adapter with the given id, or the AshSwap Aggregator if no id is given
+    /// The input can't be sent back once the swap succeeded, so the output limit (the last limit) must be zero:
+    /// swaps that need a minimum output have to use claimFromStreamSwap or claimFromStreamSwapWithAdapter
+    #[allow_multiple_var_args]
+    #[payable("*")]
+    #[endpoint(claimFromStreamSwapAsync)]
+    fn claim_from_stream_swap_async(
+        &self,
+        stream_id: u64,
+        steps: ManagedVec<AggregatorStep<Self::Api>>,
+        limits: ManagedVec<TokenAmount<Self::Api>>,
+        amount_opt: OptionalValue<BigUint>,
+        adapter_id_opt: OptionalValue<u64>,
     ) {
-        require!(steps.len() > 0, ERR_ASH_INVALID_STEPS_LENGTH);
-        require!(limits.len() > 0, ERR_ASH_INVALID_STEPS_LENGTH);
+        let adapter = match adapter_id_opt {
+            OptionalValue::Some(adapter_id) => self.get_swap_adapter(adapter_id),
+            OptionalValue::None => self.get_ash_aggregator_adapter(),
+        };
+        self.require_valid_swap_route(&steps, &limits);
+        self.require_valid_adapter_route(&adapter, &steps);
         require!(
-            steps.len() + 1 == limits.len(),
-            ERR_ASH_INVALID_LIMITS_STEPS_LENGTH
+            limits.get(limits.len() - 1).amount == 0,
+            ERR_ASYNC_SWAP_OUTPUT_LIMIT
         );
 
-        let amount = steps.get(0).amount_in.clone();
-        let payment = self.claim_from_stream_internal(stream_id, Some(amount));
         let caller = self.blockchain().get_caller();
+        let destination = self.get_claim_destination(stream_id, &caller);
 
-        // If payment token is EGLD we wrap it before doing the swap
-        if payment.token_identifier.is_egld() {
-            let _: IgnoreValue = self
-                .wrap_egld_proxy(self.wrap_egld_sc().get())
-                .wrap_egld()
-                .with_egld_transfer(payment.amount.clone())
-                .execute_on_dest_context();
-
-            let result_payments = self
-                .ash_aggregator_proxy(self.ash_aggregator_sc().get())
-                .aggregate(steps, MultiValueEncoded::from(limits))
-                .with_esdt_transfer(EsdtTokenPayment::new(
-                    self.wrap_egld_token().get(),
-                    0,
-                    payment.amount,
-                ))
-                .execute_on_dest_context::<ManagedVec<EsdtTokenPayment>>();
-
-            self.send().direct_multi(&caller, &result_payments);
-        } else {
-            let result_payments = self
-                .ash_aggregator_proxy(self.ash_aggregator_sc().get())
-                .aggregate(steps, MultiValueEncoded::from(limits))
-                .with_egld_or_single_esdt_transfer(payment)
-                .execute_on_dest_context::<ManagedVec<EsdtTokenPayment>>();
+        let amount = match amount_opt {
+            OptionalValue::Some(amount) => amount,
+            OptionalValue::None => self.get_swap_input_amount(&steps),
+        };
+        let payment = self.claim_from_stream_internal(stream_id, &destination, Some(amount));
+
+        self.swap_tokens_async(stream_id, &adapter, payment, steps, limits, &destination);
+    }
 
-            self.send().direct_multi(&caller, &result_payments);
+    fn claim_from_stream_swap_internal(
+        &self,
+        stream_id: u64,
+        adapter: &SwapAdapter<Self::Api>,
+        steps: ManagedVec<AggregatorStep<Self::Api>>,
+        limits: ManagedVec<TokenAmount<Self::Api>>,
+        amount_opt: Option<BigUint>,
+        unwrap_egld: bool,
+    ) {
+        self.require_valid_swap_route(&steps, &limits);
+        self.require_valid_adapter_route(adapter, &steps);
+
+        let caller = self.blockchain().get_caller();
+        let destination = self.get_claim_destination(stream_id, &caller);
+
+        let amount = match amount_opt {
+            Some(amount) => amount,
+            None => self.get_swap_input_amount(&steps),
+        };
+        let payment = self.claim_from_stream_internal(stream_id, &destination, Some(amount));
+
+        let (mut result_payments, unswapped_payment) =
+            self.swap_tokens(adapter, payment, steps, limits);
+
+        if unwrap_egld {
+            let (esdt_payments, egld_amount) = self.unwrap_egld_payments(result_payments);
+            result_payments = esdt_payments;
+            if egld_amount > 0 {
+                self.send().direct_egld(&destination, &egld_amount);
+            }
+        }
+
+        if !result_payments.is_empty() {
+            self.send().direct_multi(&destination, &result_payments);
+        }
+        if unswapped_payment.amount > 0 {
+            self.send().direct(
+                &destination,
+                &unswapped_payment.token_identifier,
+                unswapped_payment.token_nonce,
+                &unswapped_payment.amount,
+            );
         }
     }
 
@@ -287,7 +653,24 @@ pub trait ClaimModule:
             self.burn_stream_nft(stream_id);
         }
 
+        // A stream NFT left in the custody of the contract is sent back to its holder
+        let holder_mapper = self.stream_holder(stream_id);
+        if !holder_mapper.is_empty() {
+            if !with_burn {
+                self.send().direct_esdt(
+                    &holder_mapper.get(),
+                    self.stream_nft_token().get_token_id_ref(),
+                    self.get_stream(stream_id).nft_nonce,
+                    &BigUint::from(1u32),
+                );
+            }
+            self.clear_stream_custody(stream_id);
+        }
+
         self.stream_by_id(stream_id).clear();
+        self.stream_layout_version(stream_id).clear();
+        self.claim_destination(stream_id).clear();
+        self.stream_protocol_fee(stream_id).clear();
 
         self.finished_stream_event(stream_id);
     }
@@ -0,0 +1,116 @@
+use crate::{
+    errors::{
+        ERR_CLAIM_OPERATOR_ALREADY_ADDED, ERR_CLAIM_OPERATOR_NOT_FOUND, ERR_INVALID_CLAIM_OPERATOR,
+        ERR_ONLY_STREAM_HOLDER, ERR_STREAM_NFT_NOT_IN_CUSTODY,
+    },
+    storage::StreamRole,
+};
+
+multiversx_sc::imports!();
+
+#[multiversx_sc::module]
+pub trait ClaimOperatorModule:
+    crate::storage::StorageModule
+    + crate::events::EventsModule
+    + crate::claim::ClaimModule
+    + crate::status::StatusModule
+    + crate::stream_nft::StreamNftModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+{
+    /// This endpoint can be used by the recipient of a stream to leave the stream NFT in the custody of the contract
+    /// The caller is recorded as the holder of the stream and can register claim operators for it
+    #[payable("*")]
+    #[endpoint(depositStreamNft)]
+    fn deposit_stream_nft(&self, stream_id: u64) {
+        self.require_valid_stream_nft(stream_id, OptionalValue::Some(StreamRole::Recipient));
+
+        let caller = self.blockchain().get_caller();
+        self.stream_holder(stream_id).set(&caller);
+
+        self.deposit_stream_nft_event(stream_id, &caller);
+    }
+
+    /// This endpoint can be used by the holder of record to get the stream NFT back
+    /// All the claim operators of the stream are removed
+    #[endpoint(withdrawStreamNft)]
+    fn withdraw_stream_nft(&self, stream_id: u64) {
+        let holder = self.require_stream_holder(stream_id);
+        let stream = self.get_stream(stream_id);
+
+        self.clear_stream_custody(stream_id);
+
+        self.send().direct_esdt(
+            &holder,
+            self.stream_nft_token().get_token_id_ref(),
+            stream.nft_nonce,
+            &BigUint::from(1u32),
+        );
+
+        self.withdraw_stream_nft_event(stream_id, &holder);
+    }
+
+    #[endpoint(addClaimOperator)]
+    fn add_claim_operator(&self, stream_id: u64, operator: ManagedAddress) {
+        let holder = self.require_stream_holder(stream_id);
+        require!(operator != holder, ERR_INVALID_CLAIM_OPERATOR);
+        require!(
+            self.stream_claim_operators(stream_id)
+                .insert(operator.clone()),
+            ERR_CLAIM_OPERATOR_ALREADY_ADDED
+        );
+
+        self.add_claim_operator_event(stream_id, &holder, &operator);
+    }
+
+    #[endpoint(revokeClaimOperator)]
+    fn revoke_claim_operator(&self, stream_id: u64, operator: ManagedAddress) {
+        let holder = self.require_stream_holder(stream_id);
+        require!(
+            self.stream_claim_operators(stream_id)
+                .swap_remove(&operator),
+            ERR_CLAIM_OPERATOR_NOT_FOUND
+        );
+
+        self.revoke_claim_operator_event(stream_id, &holder, &operator);
+    }
+
+    /// This endpoint can be used by the claim operators (or the holder of record) of a stream in custody
+    /// The claimed tokens are sent to the holder of record (or to the claim destination set by the holder)
+    /// and the stream NFT stays in the custody of the contract
+    #[endpoint(claimFromStreamByOperator)]
+    fn claim_from_stream_by_operator(&self, stream_id: u64) {
+        let holder_mapper = self.stream_holder(stream_id);
+        require!(!holder_mapper.is_empty(), ERR_STREAM_NFT_NOT_IN_CUSTODY);
+
+        let holder = holder_mapper.get();
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == holder || self.stream_claim_operators(stream_id).contains(&caller),
+            ERR_INVALID_CLAIM_OPERATOR
+        );
+
+        let stream = self.get_stream(stream_id);
+        let destination = self.get_claim_destination(stream_id, &holder);
+        let (payment, _) = self.claim_stream(stream_id, stream, &holder, &destination, None);
+
+        self.send().direct(
+            &destination,
+            &payment.token_identifier,
+            payment.token_nonce,
+            &payment.amount,
+        );
+    }
+
+    fn require_stream_holder(&self, stream_id: u64) -> ManagedAddress {
+        let holder_mapper = self.stream_holder(stream_id);
+        require!(!holder_mapper.is_empty(), ERR_STREAM_NFT_NOT_IN_CUSTODY);
+
+        let holder = holder_mapper.get();
+        require!(
+            self.blockchain().get_caller() == holder,
+            ERR_ONLY_STREAM_HOLDER
+        );
+
+        holder
+    }
+}
@@ -1,22 +1,33 @@
 use crate::{
     errors::{
-        ERR_BROKER_FEE_TOO_BIG, ERR_CLIFF_TOO_BIG, ERR_END_TIME, ERR_INVALID_SEGMENTS_DEPOSIT,
-        ERR_INVALID_SEGMENTS_DURATION, ERR_START_TIME, ERR_STREAM_TO_CALLER, ERR_STREAM_TO_SC,
-        ERR_TOO_MANY_SEGMENTS, ERR_ZERO_DEPOSIT,
+        ERR_BROKER_DISABLED, ERR_BROKER_FEE_ABOVE_CAP, ERR_BROKER_FEE_TOO_BIG,
+        ERR_BROKER_NOT_REGISTERED, ERR_CLIFF_AMOUNT_TOO_BIG, ERR_CLIFF_TOO_BIG, ERR_END_TIME,
+        ERR_INSUFFICIENT_BATCH_PAYMENT, ERR_INVALID_ACCEPTANCE_DEADLINE,
+        ERR_INVALID_SEGMENTS_DEPOSIT, ERR_INVALID_SEGMENTS_DURATION, ERR_NO_STREAMS,
+        ERR_SEGMENT_EXPONENT_DENOMINATOR_TOO_BIG, ERR_SEGMENT_EXPONENT_DENOMINATOR_ZERO,
+        ERR_SEGMENT_EXPONENT_TOO_BIG, ERR_START_TIME, ERR_STREAM_TO_CALLER, ERR_STREAM_TO_SC,
+        ERR_SWAP_NO_STREAM_TOKEN, ERR_TOO_MANY_SEGMENTS, ERR_ZERO_DEPOSIT,
+    },
+    storage::{
+        AggregatorStep, BrokerFee, Segment, Stream, StreamQuote, StreamRequest, TokenAmount,
     },
-    storage::{BrokerFee, Segment, Stream},
 };
 
 multiversx_sc::imports!();
 
-const MAX_FEE: u64 = 10_00;
-const MAX_SEGMENTS: usize = 25;
+pub const MAX_FEE: u64 = 10_00;
+pub const MAX_SEGMENTS: usize = 25;
+/// Rational exponents are evaluated with a fixed point root on every claim, cancel and view,
+/// so they are bounded to keep the evaluation cost low (integer exponents are not bounded)
+pub const MAX_SEGMENT_EXPONENT: u32 = 10;
+pub const MAX_SEGMENT_EXPONENT_DENOMINATOR: u32 = 10;
 
 #[multiversx_sc::module]
 pub trait CreateStreamModule:
     crate::storage::StorageModule
     + crate::events::EventsModule
     + crate::stream_nft::StreamNftModule
+    + crate::swap::SwapModule
     + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
 {
     #[allow_multiple_var_args]
@@ -28,7 +39,9 @@ pub trait CreateStreamModule:
         segments: ManagedVec<Segment<Self::Api>>,
         cliff_opt: OptionalValue<u64>,
         can_cancel_opt: OptionalValue<bool>,
-        broker_opt: OptionalValue<BrokerFee<Self::Api>>,
+        brokers_opt: OptionalValue<ManagedVec<BrokerFee<Self::Api>>>,
+        cliff_amount_opt: OptionalValue<BigUint>,
+        acceptance_deadline_opt: OptionalValue<u64>,
     ) -> u64 {
         let start_time = self.blockchain().get_block_timestamp();
 
@@ -38,10 +51,16 @@ pub trait CreateStreamModule:
             segments,
             cliff_opt,
             can_cancel_opt,
-            broker_opt,
+            brokers_opt,
+            cliff_amount_opt,
+            acceptance_deadline_opt,
         )
     }
 
+    /// The optional cliff amount is released at once when the cliff ends
+    /// The segments must add up to the rest of the deposit
+    /// If an acceptance deadline is set, the recipient must accept the stream before it (see acceptStream),
+    /// otherwise the sender can reclaim the deposit
     #[allow_multiple_var_args]
     #[payable("*")]
     #[endpoint(createStream)]
@@ -52,66 +71,258 @@ pub trait CreateStreamModule:
         segments: ManagedVec<Segment<Self::Api>>,
         cliff_opt: OptionalValue<u64>,
         can_cancel_opt: OptionalValue<bool>,
-        broker_opt: OptionalValue<BrokerFee<Self::Api>>,
+        brokers_opt: OptionalValue<ManagedVec<BrokerFee<Self::Api>>>,
+        cliff_amount_opt: OptionalValue<BigUint>,
+        acceptance_deadline_opt: OptionalValue<u64>,
     ) -> u64 {
         let caller = self.blockchain().get_caller();
-        require!(
-            recipient != self.blockchain().get_sc_address(),
-            ERR_STREAM_TO_SC
-        );
-        require!(recipient != caller, ERR_STREAM_TO_CALLER);
+        let payment = self.call_value().egld_or_single_esdt();
 
-        let (token_identifier, token_nonce, token_amount) =
-            self.call_value().egld_or_single_esdt().into_tuple();
+        self.create_stream_internal(
+            &caller,
+            Some(recipient),
+            payment,
+            start_time,
+            segments,
+            cliff_opt.into_option().unwrap_or_default(),
+            cliff_amount_opt.into_option().unwrap_or_default(),
+            can_cancel_opt.into_option().unwrap_or(true),
+            &brokers_opt.into_option().unwrap_or_default(),
+            acceptance_deadline_opt.into_option(),
+        )
+    }
 
-        require!(token_amount > 0, ERR_ZERO_DEPOSIT);
+    /// This endpoint can be used to create multiple streams in a single transaction (e.g. payroll runs)
+    /// The payment can be a single EGLD/ESDT transfer or a multi-ESDT transfer
+    /// Each stream request specifies the token and the amount (fees included) it takes from the payment
+    /// Any amount that is not used by the streams is sent back to the caller
+    #[payable("*")]
+    #[endpoint(createStreams)]
+    fn create_streams(
+        &self,
+        streams: ManagedVec<StreamRequest<Self::Api>>,
+        brokers_opt: OptionalValue<ManagedVec<BrokerFee<Self::Api>>>,
+    ) -> MultiValueEncoded<u64> {
+        require!(!streams.is_empty(), ERR_NO_STREAMS);
 
-        let current_time = self.blockchain().get_block_timestamp();
-        require!(start_time >= current_time, ERR_START_TIME);
+        let caller = self.blockchain().get_caller();
+        let brokers = brokers_opt.into_option().unwrap_or_default();
+        let mut available_payments = self.get_batch_payments();
 
-        let stream_id = self.get_last_stream_id() + 1;
+        let mut stream_ids = MultiValueEncoded::new();
+        for request in &streams {
+            let payment = EgldOrEsdtTokenPayment::new(
+                request.payment_token,
+                request.payment_nonce,
+                request.amount,
+            );
+            self.deduct_batch_payment(&mut available_payments, &payment);
 
-        let can_cancel: bool = (&can_cancel_opt.into_option()).unwrap_or(true);
+            let stream_id = self.create_stream_internal(
+                &caller,
+                Some(request.recipient),
+                payment,
+                request.start_time,
+                request.segments,
+                request.cliff,
+                request.cliff_amount,
+                request.can_cancel,
+                &brokers,
+                None,
+            );
+            stream_ids.push(stream_id);
+        }
+
+        // Refund leftover
+        for payment in &available_payments {
+            if payment.amount > 0 {
+                self.send().direct(
+                    &caller,
+                    &payment.token_identifier,
+                    payment.token_nonce,
+                    &payment.amount,
+                );
+            }
+        }
 
-        let mut stream_amount = token_amount.clone();
-        // Check and send protocol fee
-        if !self.protocol_fee(&token_identifier).is_empty() {
-            let protocol_fee = self
-                .protocol_fee(&token_identifier)
-                .get()
-                .mul(stream_amount.clone())
-                .div(100_00u32);
+        stream_ids
+    }
+
+    /// This endpoint is using the AshSwap Aggregator to swap the payment before creating the stream
+    /// The stream token is the output token of the last swap step and the deposit is the swapped amount (fees excluded)
+    /// The segments amounts are rescaled proportionally to the actual deposit, the rounding dust going to the last segment
+    /// Any other token returned by the swap and the unswapped part of the payment are sent back to the caller
+    #[allow_multiple_var_args]
+    #[payable("*")]
+    #[endpoint(createStreamSwap)]
+    fn create_stream_swap(
+        &self,
+        recipient: ManagedAddress,
+        start_time: u64,
+        segments: ManagedVec<Segment<Self::Api>>,
+        steps: ManagedVec<AggregatorStep<Self::Api>>,
+        limits: ManagedVec<TokenAmount<Self::Api>>,
+        cliff_opt: OptionalValue<u64>,
+        can_cancel_opt: OptionalValue<bool>,
+        brokers_opt: OptionalValue<ManagedVec<BrokerFee<Self::Api>>>,
+    ) -> u64 {
+        self.require_valid_swap_route(&steps, &limits);
+
+        let caller = self.blockchain().get_caller();
+        let payment = self.call_value().egld_or_single_esdt();
+        let stream_token = steps.get(steps.len() - 1).token_out;
+
+        let adapter = self.get_ash_aggregator_adapter();
+        let (result_payments, unswapped_payment) =
+            self.swap_tokens(&adapter, payment, steps, limits);
+        if unswapped_payment.amount > 0 {
             self.send().direct(
-                &self.blockchain().get_owner_address(),
-                &token_identifier,
-                token_nonce,
-                &protocol_fee,
+                &caller,
+                &unswapped_payment.token_identifier,
+                unswapped_payment.token_nonce,
+                &unswapped_payment.amount,
             );
-            stream_amount = stream_amount.sub(protocol_fee);
-        }
-
-        // Check and send broker fee
-        if broker_opt.is_some() {
-            let broker = broker_opt.into_option().unwrap();
-            if broker.fee > BigUint::zero() {
-                require!(broker.fee <= BigUint::from(MAX_FEE), ERR_BROKER_FEE_TOO_BIG);
-                let broker_fee = broker.fee.mul(stream_amount.clone()).div(100_00u32);
-                self.send()
-                    .direct(&broker.address, &token_identifier, token_nonce, &broker_fee);
-                stream_amount = stream_amount.sub(broker_fee);
+        }
+
+        let mut stream_amount = BigUint::zero();
+        for result_payment in &result_payments {
+            if result_payment.token_identifier == stream_token && result_payment.token_nonce == 0 {
+                stream_amount += result_payment.amount;
+            } else if result_payment.amount > 0 {
+                self.send().direct_esdt(
+                    &caller,
+                    &result_payment.token_identifier,
+                    result_payment.token_nonce,
+                    &result_payment.amount,
+                );
+            }
+        }
+        require!(stream_amount > 0, ERR_SWAP_NO_STREAM_TOKEN);
+
+        let stream_token = EgldOrEsdtTokenIdentifier::esdt(stream_token);
+        let brokers = brokers_opt.into_option().unwrap_or_default();
+
+        let mut quote = self.new_stream_quote();
+        if let Err(error) = self.try_quote_fees(&mut quote, &stream_token, &stream_amount, &brokers)
+        {
+            sc_panic!(error);
+        }
+        let segments = self.rescale_segments(&segments, &quote.deposit);
+
+        self.create_stream_internal(
+            &caller,
+            Some(recipient),
+            EgldOrEsdtTokenPayment::new(stream_token, 0, stream_amount),
+            start_time,
+            segments,
+            cliff_opt.into_option().unwrap_or_default(),
+            BigUint::zero(),
+            can_cancel_opt.into_option().unwrap_or(true),
+            &brokers,
+            None,
+        )
+    }
+
+    /// This endpoint can be used to create a stream for a recipient that is not known yet (e.g. a link or a QR code)
+    /// The stream NFT is held by the contract and bound to the sha256 hash of a secret
+    /// The first address that commits to the secret and then reveals it before the expiry becomes the recipient
+    /// (see commitRedeem and redeemStream),
+    /// otherwise the sender can reclaim the deposit after the expiry (see reclaimStream)
+    #[allow_multiple_var_args]
+    #[payable("*")]
+    #[endpoint(createHashLockedStream)]
+    fn create_hash_locked_stream(
+        &self,
+        secret_hash: ManagedByteArray<Self::Api, 32>,
+        expiry: u64,
+        start_time: u64,
+        segments: ManagedVec<Segment<Self::Api>>,
+        cliff_opt: OptionalValue<u64>,
+        can_cancel_opt: OptionalValue<bool>,
+        brokers_opt: OptionalValue<ManagedVec<BrokerFee<Self::Api>>>,
+        cliff_amount_opt: OptionalValue<BigUint>,
+    ) -> u64 {
+        let caller = self.blockchain().get_caller();
+        let payment = self.call_value().egld_or_single_esdt();
+
+        let stream_id = self.create_stream_internal(
+            &caller,
+            None,
+            payment,
+            start_time,
+            segments,
+            cliff_opt.into_option().unwrap_or_default(),
+            cliff_amount_opt.into_option().unwrap_or_default(),
+            can_cancel_opt.into_option().unwrap_or(true),
+            &brokers_opt.into_option().unwrap_or_default(),
+            Some(expiry),
+        );
+
+        self.stream_secret_hash(stream_id).set(secret_hash);
+
+        stream_id
+    }
+
+    fn create_stream_internal(
+        &self,
+        sender: &ManagedAddress,
+        recipient: Option<ManagedAddress>,
+        payment: EgldOrEsdtTokenPayment,
+        start_time: u64,
+        segments: ManagedVec<Segment<Self::Api>>,
+        cliff: u64,
+        cliff_amount: BigUint,
+        can_cancel: bool,
+        brokers: &ManagedVec<BrokerFee<Self::Api>>,
+        acceptance_deadline: Option<u64>,
+    ) -> u64 {
+        // Streams without a recipient (e.g. hash locked streams) have their NFT held by the contract
+        let sc_address = self.blockchain().get_sc_address();
+        let nft_holder = match recipient {
+            Some(recipient) => {
+                require!(recipient != sc_address, ERR_STREAM_TO_SC);
+                require!(&recipient != sender, ERR_STREAM_TO_CALLER);
+                recipient
             }
+            None => sc_address.clone(),
+        };
+        if let Some(deadline) = acceptance_deadline {
+            require!(
+                deadline > self.blockchain().get_block_timestamp(),
+                ERR_INVALID_ACCEPTANCE_DEADLINE
+            );
         }
 
-        let stream_duration = self.validate_stream_segments(&stream_amount, &segments);
-        let end_time = start_time + stream_duration;
-        require!(end_time > start_time, ERR_END_TIME);
+        let (token_identifier, token_nonce, token_amount) = payment.into_tuple();
 
-        let cliff = cliff_opt.into_option().unwrap_or_default();
-        require!(start_time + cliff < end_time, ERR_CLIFF_TOO_BIG);
+        let mut quote = self.new_stream_quote();
+        if let Err(error) = self.try_quote_stream(
+            &mut quote,
+            &token_identifier,
+            &token_amount,
+            start_time,
+            &segments,
+            cliff,
+            &cliff_amount,
+            brokers,
+        ) {
+            sc_panic!(error);
+        }
+
+        let stream_id = self.get_next_stream_id();
 
-        let stream = Stream {
-            sender: caller.clone(),
-            nft_nonce: stream_id,
+        let stream_amount = self.apply_fees(
+            stream_id,
+            &token_identifier,
+            token_nonce,
+            &token_amount,
+            brokers,
+        );
+        let end_time = quote.end_time;
+
+        let mut stream = Stream {
+            sender: sender.clone(),
+            nft_nonce: 0,
             payment_token: token_identifier.clone(),
             payment_nonce: token_nonce,
             deposit: stream_amount.clone(),
@@ -120,26 +331,30 @@ pub trait CreateStreamModule:
             start_time,
             end_time,
             cliff,
+            cliff_amount: cliff_amount.clone(),
             segments: segments.clone(),
             balances_after_cancel: None,
+            acceptance_deadline,
         };
 
-        let stream_nft_nonce = self.mint_stream_nft(&stream);
+        stream.nft_nonce = self.mint_stream_nft(stream_id, &stream);
 
-        self.stream_by_id(stream_id).set(&stream);
+        self.save_stream(stream_id, &stream);
 
-        self.send().direct_esdt(
-            &recipient,
-            self.stream_nft_token().get_token_id_ref(),
-            stream_nft_nonce,
-            &BigUint::from(1u64),
-        );
+        if nft_holder != sc_address {
+            self.send().direct_esdt(
+                &nft_holder,
+                self.stream_nft_token().get_token_id_ref(),
+                stream.nft_nonce,
+                &BigUint::from(1u64),
+            );
+        }
 
         self.create_stream_event(
-            &caller,
-            &recipient,
+            sender,
+            &nft_holder,
             self.stream_nft_token().get_token_id_ref(),
-            stream_nft_nonce,
+            stream.nft_nonce,
             &token_identifier,
             token_nonce,
             &stream_amount,
@@ -148,33 +363,381 @@ pub trait CreateStreamModule:
             end_time,
             can_cancel,
             cliff,
+            &cliff_amount,
             &segments,
         );
 
         stream_id
     }
 
+    /// Computes the fees, the deposit and the end time of a stream without creating it
+    /// The first validation error that would make the stream creation fail is returned as well
+    #[view(quoteCreateStream)]
+    fn quote_create_stream(
+        &self,
+        token: EgldOrEsdtTokenIdentifier,
+        amount: BigUint,
+        start_time: u64,
+        segments: ManagedVec<Segment<Self::Api>>,
+        cliff: u64,
+        brokers_opt: OptionalValue<ManagedVec<BrokerFee<Self::Api>>>,
+        cliff_amount_opt: OptionalValue<BigUint>,
+    ) -> StreamQuote<Self::Api> {
+        let mut quote = self.new_stream_quote();
+        if let Err(error) = self.try_quote_stream(
+            &mut quote,
+            &token,
+            &amount,
+            start_time,
+            &segments,
+            cliff,
+            &cliff_amount_opt.into_option().unwrap_or_default(),
+            &brokers_opt.into_option().unwrap_or_default(),
+        ) {
+            quote.error = Some(ManagedBuffer::from(error));
+        }
+
+        quote
+    }
+
+    fn new_stream_quote(&self) -> StreamQuote<Self::Api> {
+        StreamQuote {
+            protocol_fee: BigUint::zero(),
+            broker_fee: BigUint::zero(),
+            deposit: BigUint::zero(),
+            end_time: 0,
+            error: None,
+        }
+    }
+
+    /// Fills the stream quote step by step, stopping at the first validation error
+    /// This is the validation used when creating streams as well
+    fn try_quote_stream(
+        &self,
+        quote: &mut StreamQuote<Self::Api>,
+        token_identifier: &EgldOrEsdtTokenIdentifier,
+        amount: &BigUint,
+        start_time: u64,
+        segments: &ManagedVec<Segment<Self::Api>>,
+        cliff: u64,
+        cliff_amount: &BigUint,
+        brokers: &ManagedVec<BrokerFee<Self::Api>>,
+    ) -> Result<(), &'static str> {
+        if *amount == 0 {
+            return Err(ERR_ZERO_DEPOSIT);
+        }
+
+        let current_time = self.blockchain().get_block_timestamp();
+        if start_time < current_time {
+            return Err(ERR_START_TIME);
+        }
+
+        self.try_quote_fees(quote, token_identifier, amount, brokers)?;
+
+        if *cliff_amount > quote.deposit {
+            return Err(ERR_CLIFF_AMOUNT_TOO_BIG);
+        }
+
+        let segments_deposit = &quote.deposit - cliff_amount;
+        let stream_duration = self.try_validate_stream_segments(&segments_deposit, segments)?;
+        quote.end_time = start_time + stream_duration;
+        if quote.end_time <= start_time {
+            return Err(ERR_END_TIME);
+        }
+
+        if start_time + cliff >= quote.end_time {
+            return Err(ERR_CLIFF_TOO_BIG);
+        }
+
+        Ok(())
+    }
+
+    /// Fills the protocol fee, the broker fee and the resulting deposit of the stream quote
+    fn try_quote_fees(
+        &self,
+        quote: &mut StreamQuote<Self::Api>,
+        token_identifier: &EgldOrEsdtTokenIdentifier,
+        amount: &BigUint,
+        brokers: &ManagedVec<BrokerFee<Self::Api>>,
+    ) -> Result<(), &'static str> {
+        quote.protocol_fee = self.compute_protocol_fee(token_identifier, amount);
+        let amount_after_protocol_fee = amount - &quote.protocol_fee;
+
+        quote.broker_fee = BigUint::zero();
+        for broker in &self.try_get_broker_fees(brokers)? {
+            quote.broker_fee += self.compute_broker_fee(&broker.fee, &amount_after_protocol_fee);
+        }
+        quote.deposit = amount_after_protocol_fee - &quote.broker_fee;
+
+        Ok(())
+    }
+
+    fn compute_protocol_fee(
+        &self,
+        token_identifier: &EgldOrEsdtTokenIdentifier,
+        amount: &BigUint,
+    ) -> BigUint {
+        if self.protocol_fee(token_identifier).is_empty() {
+            return BigUint::zero();
+        }
+
+        self.protocol_fee(token_identifier)
+            .get()
+            .mul(amount)
+            .div(100_00u32)
+    }
+
+    fn compute_broker_fee(&self, fee: &BigUint, amount: &BigUint) -> BigUint {
+        (fee * amount).div(100_00u32)
+    }
+
+    /// Accrues the protocol fee, sends the broker fees and returns the remaining amount to be streamed
+    fn apply_fees(
+        &self,
+        stream_id: u64,
+        token_identifier: &EgldOrEsdtTokenIdentifier,
+        token_nonce: u64,
+        amount: &BigUint,
+        brokers: &ManagedVec<BrokerFee<Self::Api>>,
+    ) -> BigUint {
+        // Check and accrue protocol fee
+        let protocol_fee = self.compute_protocol_fee(token_identifier, amount);
+        self.accrue_protocol_fee(token_identifier, token_nonce, &protocol_fee);
+        if protocol_fee > 0 {
+            self.stream_protocol_fee(stream_id)
+                .update(|stream_protocol_fee| *stream_protocol_fee += &protocol_fee);
+        }
+        let mut stream_amount = amount - &protocol_fee;
+
+        // Check and send broker fees
+        let broker_fees = match self.try_get_broker_fees(brokers) {
+            Ok(broker_fees) => broker_fees,
+            Err(error) => sc_panic!(error),
+        };
+
+        let mut total_broker_fee = BigUint::zero();
+        for broker in &broker_fees {
+            if broker.fee == 0 {
+                continue;
+            }
+
+            let broker_fee = self.compute_broker_fee(&broker.fee, &stream_amount);
+            self.send()
+                .direct(&broker.address, token_identifier, token_nonce, &broker_fee);
+            self.broker_fee_volume(&broker.address, token_identifier)
+                .update(|volume| *volume += &broker_fee);
+
+            self.broker_fee_event(
+                stream_id,
+                &broker.address,
+                token_identifier,
+                token_nonce,
+                &broker_fee,
+            );
+
+            total_broker_fee += broker_fee;
+        }
+        stream_amount -= total_broker_fee;
+
+        stream_amount
+    }
+
+    /// Resolves the fees of the stream brokers and validates them against the broker registry
+    /// The cap of the first broker bounds the combined fee of all the brokers
+    /// and its default fee is used when it's passed with a zero fee
+    /// Every broker must be enabled if registered, and registered when the broker registry is enabled
+    fn try_get_broker_fees(
+        &self,
+        brokers: &ManagedVec<BrokerFee<Self::Api>>,
+    ) -> Result<ManagedVec<BrokerFee<Self::Api>>, &'static str> {
+        let mut broker_fees = brokers.clone();
+        if broker_fees.is_empty() {
+            return Ok(broker_fees);
+        }
+
+        let mut main_broker = broker_fees.get(0);
+
+        let broker_config_mapper = self.broker_config(&main_broker.address);
+        let (max_fee, max_fee_error) = if broker_config_mapper.is_empty() {
+            if self.broker_registry_enabled().get() {
+                return Err(ERR_BROKER_NOT_REGISTERED);
+            }
+            (BigUint::from(MAX_FEE), ERR_BROKER_FEE_TOO_BIG)
+        } else {
+            let broker_config = broker_config_mapper.get();
+            if !broker_config.is_enabled {
+                return Err(ERR_BROKER_DISABLED);
+            }
+
+            if main_broker.fee == 0 {
+                if let Some(default_fee) = broker_config.default_fee {
+                    main_broker.fee = default_fee;
+                    let _ = broker_fees.set(0, &main_broker);
+                }
+            }
+            (broker_config.max_fee, ERR_BROKER_FEE_ABOVE_CAP)
+        };
+
+        let mut total_fee = BigUint::zero();
+        for (index, broker) in broker_fees.iter().enumerate() {
+            if index > 0 {
+                self.try_validate_broker(&broker.address)?;
+            }
+
+            total_fee += broker.fee;
+        }
+        if total_fee > max_fee {
+            return Err(max_fee_error);
+        }
+
+        Ok(broker_fees)
+    }
+
+    fn try_validate_broker(&self, address: &ManagedAddress) -> Result<(), &'static str> {
+        let broker_config_mapper = self.broker_config(address);
+        if broker_config_mapper.is_empty() {
+            if self.broker_registry_enabled().get() {
+                return Err(ERR_BROKER_NOT_REGISTERED);
+            }
+        } else if !broker_config_mapper.get().is_enabled {
+            return Err(ERR_BROKER_DISABLED);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the payments received by a batch creation, merged by token
+    fn get_batch_payments(&self) -> ManagedVec<EgldOrEsdtTokenPayment> {
+        let mut payments = ManagedVec::new();
+
+        let egld_value = self.call_value().egld_value().clone_value();
+        if egld_value > 0 {
+            payments.push(EgldOrEsdtTokenPayment::new(
+                EgldOrEsdtTokenIdentifier::egld(),
+                0,
+                egld_value,
+            ));
+        }
+
+        for esdt_payment in self.call_value().all_esdt_transfers().iter() {
+            let payment = EgldOrEsdtTokenPayment::new(
+                EgldOrEsdtTokenIdentifier::esdt(esdt_payment.token_identifier),
+                esdt_payment.token_nonce,
+                esdt_payment.amount,
+            );
+            match self.find_batch_payment(&payments, &payment) {
+                Some(index) => {
+                    let mut existing_payment = payments.get(index);
+                    existing_payment.amount += payment.amount;
+                    let _ = payments.set(index, &existing_payment);
+                }
+                None => payments.push(payment),
+            }
+        }
+
+        payments
+    }
+
+    fn deduct_batch_payment(
+        &self,
+        available_payments: &mut ManagedVec<EgldOrEsdtTokenPayment>,
+        payment: &EgldOrEsdtTokenPayment,
+    ) {
+        let index_opt = self.find_batch_payment(available_payments, payment);
+        require!(index_opt.is_some(), ERR_INSUFFICIENT_BATCH_PAYMENT);
+
+        let index = index_opt.unwrap();
+        let mut available_payment = available_payments.get(index);
+        require!(
+            available_payment.amount >= payment.amount,
+            ERR_INSUFFICIENT_BATCH_PAYMENT
+        );
+        available_payment.amount -= &payment.amount;
+        let _ = available_payments.set(index, &available_payment);
+    }
+
+    fn find_batch_payment(
+        &self,
+        payments: &ManagedVec<EgldOrEsdtTokenPayment>,
+        payment: &EgldOrEsdtTokenPayment,
+    ) -> Option<usize> {
+        payments.iter().position(|p| {
+            p.token_identifier == payment.token_identifier && p.token_nonce == payment.token_nonce
+        })
+    }
+
+    /// Rescales the segments amounts proportionally so they add up to the deposit
+    /// The rounding dust is added to the last segment
+    fn rescale_segments(
+        &self,
+        segments: &ManagedVec<Segment<Self::Api>>,
+        deposit: &BigUint,
+    ) -> ManagedVec<Segment<Self::Api>> {
+        let mut segments_total = BigUint::zero();
+        for segment in segments {
+            segments_total += segment.amount;
+        }
+        require!(segments_total > 0, ERR_INVALID_SEGMENTS_DEPOSIT);
+
+        let mut rescaled_segments = ManagedVec::new();
+        let mut rescaled_total = BigUint::zero();
+        for (index, mut segment) in segments.iter().enumerate() {
+            segment.amount = if index == segments.len() - 1 {
+                deposit - &rescaled_total
+            } else {
+                &segment.amount * deposit / &segments_total
+            };
+            rescaled_total += &segment.amount;
+            rescaled_segments.push(segment);
+        }
+
+        rescaled_segments
+    }
+
     fn validate_stream_segments(
         &self,
         deposit: &BigUint,
         segments: &ManagedVec<Segment<Self::Api>>,
     ) -> u64 {
-        require!(segments.len() <= MAX_SEGMENTS, ERR_TOO_MANY_SEGMENTS);
+        match self.try_validate_stream_segments(deposit, segments) {
+            Ok(segments_duration) => segments_duration,
+            Err(error) => sc_panic!(error),
+        }
+    }
+
+    fn try_validate_stream_segments(
+        &self,
+        deposit: &BigUint,
+        segments: &ManagedVec<Segment<Self::Api>>,
+    ) -> Result<u64, &'static str> {
+        if segments.len() > MAX_SEGMENTS {
+            return Err(ERR_TOO_MANY_SEGMENTS);
+        }
 
         let mut segments_duration = 0u64;
         let mut segments_total_deposit = BigUint::zero();
         for segment in segments {
-            require!(segment.duration > 0, ERR_INVALID_SEGMENTS_DURATION);
+            if segment.duration == 0 {
+                return Err(ERR_INVALID_SEGMENTS_DURATION);
+            }
+            if segment.exponent_denominator == 0 {
+                return Err(ERR_SEGMENT_EXPONENT_DENOMINATOR_ZERO);
+            }
+            if segment.exponent_denominator > 1 && segment.exponent > MAX_SEGMENT_EXPONENT {
+                return Err(ERR_SEGMENT_EXPONENT_TOO_BIG);
+            }
+            if segment.exponent_denominator > MAX_SEGMENT_EXPONENT_DENOMINATOR {
+                return Err(ERR_SEGMENT_EXPONENT_DENOMINATOR_TOO_BIG);
+            }
 
             segments_duration += segment.duration;
             segments_total_deposit += segment.amount;
         }
 
-        require!(
-            &segments_total_deposit == deposit,
-            ERR_INVALID_SEGMENTS_DEPOSIT
-        );
+        if &segments_total_deposit != deposit {
+            return Err(ERR_INVALID_SEGMENTS_DEPOSIT);
+        }
 
-        segments_duration
+        Ok(segments_duration)
     }
 }
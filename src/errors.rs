@@ -10,21 +10,75 @@ pub const ERR_CANCEL_ONLY_SENDER: &str = "Only sender can renounce the cancel st
 pub const ERR_INVALID_STREAM: &str = "Stream does not exist";
 pub const ERR_STREAM_IS_NOT_CANCELLED: &str = "This stream is not cancelled";
 pub const ERR_PROTOCOL_FEE_ZERO: &str = "Protocol fee can't be set to zero";
+pub const ERR_PROTOCOL_FEE_TOO_BIG: &str = "Protocol fee can't be bigger than 10%";
+pub const ERR_PROTOCOL_FEE_NOT_ANNOUNCED: &str = "Protocol fee increase must be announced";
+pub const ERR_NO_PENDING_PROTOCOL_FEE: &str = "No pending protocol fee";
+pub const ERR_PROTOCOL_FEE_DELAY: &str = "Protocol fee delay has not passed";
+pub const ERR_PROTOCOL_FEE_DELAY_DECREASE: &str = "Protocol fee delay decrease must be announced";
+pub const ERR_NO_PENDING_PROTOCOL_FEE_DELAY: &str = "No pending protocol fee delay";
+pub const ERR_PROTOCOL_FEE_DELAY_TOO_BIG: &str = "Protocol fee delay is too big";
+pub const ERR_INVALID_TREASURY_SHARES: &str = "Treasury shares must add up to 100%";
+pub const ERR_NO_PROTOCOL_FEES: &str = "No protocol fees to withdraw";
 pub const ERR_CANT_CLAIM: &str = "Stream can't be claimed";
 pub const ERR_CLIFF_TOO_BIG: &str = "Cliff period bigger than stream duration";
 pub const ERR_BROKER_FEE_TOO_BIG: &str = "Broker fee can't be bigger than 10%";
+pub const ERR_BROKER_FEE_ABOVE_CAP: &str = "Broker fee is bigger than the broker cap";
+pub const ERR_BROKER_NOT_REGISTERED: &str = "Broker is not registered";
+pub const ERR_BROKER_DISABLED: &str = "Broker is disabled";
 pub const ERR_TOKEN_ALREADY_ISSUED: &str = "Token already issued";
 pub const ERR_TOKEN_NOT_ISSUED: &str = "Token not issued";
 pub const ERR_INVALID_NFT_TOKEN: &str = "Invalid token";
 pub const ERR_SEND_ONE_STREAM_NFT: &str = "You can only send one stream NFT at a time";
+pub const ERR_NO_STREAM_NFTS: &str = "No stream NFTs sent";
+pub const ERR_CLAIM_DESTINATION_SC: &str = "Claim destination can't be the contract";
+pub const ERR_CANT_SETTLE: &str = "Stream can't be settled";
+pub const ERR_STREAM_NFT_NOT_IN_CUSTODY: &str = "Stream NFT is not in custody";
+pub const ERR_ONLY_STREAM_HOLDER: &str = "Only the stream holder can do this";
+pub const ERR_INVALID_CLAIM_OPERATOR: &str = "Invalid claim operator";
+pub const ERR_CLAIM_OPERATOR_ALREADY_ADDED: &str = "Claim operator already added";
+pub const ERR_CLAIM_OPERATOR_NOT_FOUND: &str = "Claim operator not found";
 pub const ERR_INVALID_NFT_TOKEN_NONCE: &str = "Invalid token nonce";
 pub const ERR_INVALID_ROLE: &str = "Unauthorized! Invalid Role";
 pub const ERR_SEGMENT_EXPONENT_DENOMINATOR_ZERO: &str =
     "Segment exponent denominator must be greater than 0";
+pub const ERR_SEGMENT_EXPONENT_TOO_BIG: &str = "Segment exponent is too big";
+pub const ERR_SEGMENT_EXPONENT_DENOMINATOR_TOO_BIG: &str =
+    "Segment exponent denominator is too big";
 pub const ERR_TOO_MANY_SEGMENTS: &str = "Too many segments";
 pub const ERR_INVALID_SEGMENTS_DURATION: &str = "Invalid segments duration";
 pub const ERR_INVALID_SEGMENTS_DEPOSIT: &str = "Invalid segments deposit";
+pub const ERR_NO_STREAMS: &str = "No streams to create";
+pub const ERR_INSUFFICIENT_BATCH_PAYMENT: &str = "Insufficient payment for the streams batch";
+pub const ERR_TOP_UP_ONLY_SENDER: &str = "Only sender can top up the stream";
+pub const ERR_CANT_TOP_UP: &str = "This stream can't be topped up";
+pub const ERR_INVALID_TOP_UP_TOKEN: &str = "Top up token doesn't match the stream token";
+pub const ERR_CLIFF_AMOUNT_TOO_BIG: &str = "Cliff amount can't be bigger than the deposit";
+pub const ERR_INVALID_ACCEPTANCE_DEADLINE: &str = "Acceptance deadline must be in the future";
+pub const ERR_NOT_AWAITING_ACCEPTANCE: &str = "Stream is not awaiting acceptance";
+pub const ERR_ACCEPTANCE_DEADLINE_PASSED: &str = "Acceptance deadline has passed";
+pub const ERR_ACCEPTANCE_DEADLINE_NOT_PASSED: &str = "Acceptance deadline has not passed";
+pub const ERR_RECLAIM_ONLY_SENDER: &str = "Only sender can reclaim the stream";
+pub const ERR_STREAM_NOT_HASH_LOCKED: &str = "Stream is not hash locked";
+pub const ERR_INVALID_SECRET: &str = "Invalid secret";
+pub const ERR_REDEEM_NOT_COMMITTED: &str = "Redeem was not committed in a previous block";
+pub const ERR_STREAM_HASH_LOCKED: &str = "Stream is still hash locked";
+pub const ERR_SWAP_NO_STREAM_TOKEN: &str = "Swap didn't return any stream token";
+pub const ERR_CANT_SPLIT: &str = "Stream can't be split";
+pub const ERR_INVALID_SPLIT_SHARES: &str = "Invalid split shares";
+pub const ERR_SPLIT_ABOVE_STREAMED: &str = "Split stream would have more claimed than streamed";
 /// AshSwap Aggregator errors
 pub const ERR_ASH_INVALID_STEPS_LENGTH: &str = "Ash: Invalid steps length";
 pub const ERR_ASH_INVALID_LIMITS_LENGTH: &str = "Ash: Invalid limits length";
 pub const ERR_ASH_INVALID_LIMITS_STEPS_LENGTH: &str = "Ash: Invalid steps or limits length";
+pub const ERR_ASH_INVALID_INPUT_TOKEN: &str = "Ash: Invalid input token";
+pub const ERR_ASH_INVALID_INPUT_AMOUNT: &str = "Ash: Invalid input amount";
+pub const ERR_ASH_OUTPUT_BELOW_LIMIT: &str = "Ash: Output amount below the limit";
+pub const ERR_ASYNC_SWAP_OUTPUT_LIMIT: &str = "Async swaps can't have a minimum output";
+/// Swap adapters errors
+pub const ERR_SWAP_ADAPTER_NOT_FOUND: &str = "Swap adapter not found";
+pub const ERR_SWAP_ADAPTER_INVALID_STEPS: &str = "Swap adapter doesn't support the swap steps";
+/// Claim and call errors
+pub const ERR_CALL_NOT_WHITELISTED: &str = "Call target is not whitelisted";
+pub const ERR_CALL_ALREADY_WHITELISTED: &str = "Call target is already whitelisted";
+pub const ERR_CALL_INVALID_RETURN_TOKEN: &str =
+    "Call target sent back a token that is not whitelisted";
@@ -1,4 +1,4 @@
-use crate::storage::Segment;
+use crate::storage::{BrokerConfig, Segment, SwapAdapter, TreasuryShare};
 
 multiversx_sc::imports!();
 
@@ -19,6 +19,27 @@ pub trait EventsModule {
         #[indexed] end_time: u64,
         #[indexed] can_cancel: bool,
         #[indexed] cliff: u64,
+        #[indexed] cliff_amount: &BigUint,
+        #[indexed] segments: &ManagedVec<Segment<Self::Api>>,
+    );
+
+    #[event("brokerFee")]
+    fn broker_fee_event(
+        &self,
+        #[indexed] stream_id: u64,
+        #[indexed] broker: &ManagedAddress,
+        #[indexed] token: &EgldOrEsdtTokenIdentifier,
+        #[indexed] nonce: u64,
+        #[indexed] amount: &BigUint,
+    );
+
+    #[event("topUpStream")]
+    fn top_up_stream_event(
+        &self,
+        #[indexed] stream_id: u64,
+        #[indexed] amount: &BigUint,
+        #[indexed] amount_with_fees: &BigUint,
+        #[indexed] end_time: u64,
         #[indexed] segments: &ManagedVec<Segment<Self::Api>>,
     );
 
@@ -28,6 +49,45 @@ pub trait EventsModule {
         #[indexed] stream_id: u64,
         #[indexed] amount: &BigUint,
         #[indexed] recipient: &ManagedAddress,
+        #[indexed] destination: &ManagedAddress,
+    );
+
+    #[event("depositStreamNft")]
+    fn deposit_stream_nft_event(
+        &self,
+        #[indexed] stream_id: u64,
+        #[indexed] holder: &ManagedAddress,
+    );
+
+    #[event("withdrawStreamNft")]
+    fn withdraw_stream_nft_event(
+        &self,
+        #[indexed] stream_id: u64,
+        #[indexed] holder: &ManagedAddress,
+    );
+
+    #[event("addClaimOperator")]
+    fn add_claim_operator_event(
+        &self,
+        #[indexed] stream_id: u64,
+        #[indexed] holder: &ManagedAddress,
+        #[indexed] operator: &ManagedAddress,
+    );
+
+    #[event("revokeClaimOperator")]
+    fn revoke_claim_operator_event(
+        &self,
+        #[indexed] stream_id: u64,
+        #[indexed] holder: &ManagedAddress,
+        #[indexed] operator: &ManagedAddress,
+    );
+
+    #[event("claimDestination")]
+    fn claim_destination_event(
+        &self,
+        #[indexed] stream_id: u64,
+        #[indexed] holder: &ManagedAddress,
+        #[indexed] destination: &Option<ManagedAddress>,
     );
 
     #[event("cancelStream")]
@@ -41,6 +101,158 @@ pub trait EventsModule {
     #[event("finishedStream")]
     fn finished_stream_event(&self, #[indexed] stream_id: u64);
 
+    #[event("settleStream")]
+    fn settle_stream_event(
+        &self,
+        #[indexed] stream_id: u64,
+        #[indexed] keeper: &ManagedAddress,
+        #[indexed] keeper_fee: &BigUint,
+    );
+
     #[event("renounceCancelStream")]
     fn renounce_cancel_stream_event(&self, #[indexed] stream_id: u64);
+
+    #[event("acceptStream")]
+    fn accept_stream_event(&self, #[indexed] stream_id: u64, #[indexed] recipient: &ManagedAddress);
+
+    #[event("commitRedeem")]
+    fn commit_redeem_event(
+        &self,
+        #[indexed] stream_id: u64,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] commitment: &ManagedByteArray<Self::Api, 32>,
+    );
+
+    #[event("redeemStream")]
+    fn redeem_stream_event(&self, #[indexed] stream_id: u64, #[indexed] recipient: &ManagedAddress);
+
+    #[event("reclaimStream")]
+    fn reclaim_stream_event(
+        &self,
+        #[indexed] stream_id: u64,
+        #[indexed] sender: &ManagedAddress,
+        #[indexed] amount: &BigUint,
+    );
+
+    #[event("protocolFee")]
+    fn protocol_fee_event(
+        &self,
+        #[indexed] token: &EgldOrEsdtTokenIdentifier,
+        #[indexed] value: &BigUint,
+    );
+
+    #[event("announceProtocolFee")]
+    fn announce_protocol_fee_event(
+        &self,
+        #[indexed] token: &EgldOrEsdtTokenIdentifier,
+        #[indexed] value: &BigUint,
+        #[indexed] apply_after: u64,
+    );
+
+    #[event("cancelProtocolFee")]
+    fn cancel_protocol_fee_event(&self, #[indexed] token: &EgldOrEsdtTokenIdentifier);
+
+    #[event("protocolFeeDelay")]
+    fn protocol_fee_delay_event(&self, #[indexed] delay: u64);
+
+    #[event("announceProtocolFeeDelay")]
+    fn announce_protocol_fee_delay_event(&self, #[indexed] delay: u64, #[indexed] apply_after: u64);
+
+    #[event("cancelProtocolFeeDelay")]
+    fn cancel_protocol_fee_delay_event(&self);
+
+    #[event("brokerConfig")]
+    fn broker_config_event(
+        &self,
+        #[indexed] address: &ManagedAddress,
+        #[indexed] broker_config: &BrokerConfig<Self::Api>,
+    );
+
+    #[event("removeBroker")]
+    fn remove_broker_event(&self, #[indexed] address: &ManagedAddress);
+
+    #[event("brokerRegistryEnabled")]
+    fn broker_registry_enabled_event(&self, #[indexed] is_enabled: bool);
+
+    #[event("protocolFeeTreasury")]
+    fn protocol_fee_treasury_event(
+        &self,
+        #[indexed] treasury: &ManagedVec<TreasuryShare<Self::Api>>,
+    );
+
+    #[event("keeperFee")]
+    fn keeper_fee_event(
+        &self,
+        #[indexed] token: &EgldOrEsdtTokenIdentifier,
+        #[indexed] fee: &BigUint,
+    );
+
+    #[event("splitStream")]
+    fn split_stream_event(
+        &self,
+        #[indexed] stream_id: u64,
+        #[indexed] holder: &ManagedAddress,
+        #[indexed] new_stream_ids: &ManagedVec<u64>,
+    );
+
+    #[event("whitelistCallEndpoint")]
+    fn whitelist_call_endpoint_event(
+        &self,
+        #[indexed] target: &ManagedAddress,
+        #[indexed] endpoint: &ManagedBuffer,
+        #[indexed] return_tokens: &ManagedVec<TokenIdentifier>,
+    );
+
+    #[event("callReturnTokens")]
+    fn call_return_tokens_event(
+        &self,
+        #[indexed] target: &ManagedAddress,
+        #[indexed] endpoint: &ManagedBuffer,
+        #[indexed] return_tokens: &ManagedVec<TokenIdentifier>,
+    );
+
+    #[event("removeCallEndpoint")]
+    fn remove_call_endpoint_event(
+        &self,
+        #[indexed] target: &ManagedAddress,
+        #[indexed] endpoint: &ManagedBuffer,
+    );
+
+    #[event("swapFailed")]
+    fn swap_failed_event(
+        &self,
+        #[indexed] stream_id: u64,
+        #[indexed] destination: &ManagedAddress,
+        #[indexed] token: &TokenIdentifier,
+        #[indexed] amount: &BigUint,
+        #[indexed] error_message: &ManagedBuffer,
+    );
+
+    #[event("swapAdapter")]
+    fn swap_adapter_event(
+        &self,
+        #[indexed] adapter_id: u64,
+        #[indexed] adapter: &SwapAdapter<Self::Api>,
+    );
+
+    #[event("removeSwapAdapter")]
+    fn remove_swap_adapter_event(&self, #[indexed] adapter_id: u64);
+
+    #[event("ashAggregatorSc")]
+    fn ash_aggregator_sc_event(&self, #[indexed] address: &ManagedAddress);
+
+    #[event("wrapEgld")]
+    fn wrap_egld_event(
+        &self,
+        #[indexed] wrap_egld_sc: &ManagedAddress,
+        #[indexed] wrap_egld_token: &TokenIdentifier,
+    );
+
+    #[event("withdrawProtocolFees")]
+    fn withdraw_protocol_fees_event(
+        &self,
+        #[indexed] token: &EgldOrEsdtTokenIdentifier,
+        #[indexed] nonce: u64,
+        #[indexed] amount: &BigUint,
+    );
 }
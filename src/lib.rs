@@ -2,15 +2,22 @@
 
 multiversx_sc::imports!();
 
+pub mod accept_stream;
 pub mod cancel_stream;
 pub mod claim;
+pub mod claim_operator;
 pub mod create_stream;
 pub mod errors;
 mod events;
 mod owner;
 mod status;
+pub mod restream;
 pub mod storage;
+pub mod settle_stream;
+pub mod split_stream;
 pub mod stream_nft;
+pub mod swap;
+pub mod top_up_stream;
 
 #[multiversx_sc::contract]
 pub trait CoinDrip:
@@ -22,6 +29,13 @@ pub trait CoinDrip:
     + owner::OwnerModule
     + status::StatusModule
     + stream_nft::StreamNftModule
+    + swap::SwapModule
+    + top_up_stream::TopUpStreamModule
+    + accept_stream::AcceptStreamModule
+    + claim_operator::ClaimOperatorModule
+    + settle_stream::SettleStreamModule
+    + restream::RestreamModule
+    + split_stream::SplitStreamModule
     + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
 {
     #[init]
@@ -1,28 +1,522 @@
-use crate::errors::ERR_PROTOCOL_FEE_ZERO;
+use crate::{
+    create_stream::MAX_FEE,
+    errors::{
+        ERR_BROKER_FEE_ABOVE_CAP, ERR_BROKER_FEE_TOO_BIG, ERR_BROKER_NOT_REGISTERED,
+        ERR_CALL_ALREADY_WHITELISTED, ERR_CALL_NOT_WHITELISTED, ERR_INVALID_TREASURY_SHARES,
+        ERR_NO_PENDING_PROTOCOL_FEE, ERR_NO_PENDING_PROTOCOL_FEE_DELAY, ERR_NO_PROTOCOL_FEES,
+        ERR_PROTOCOL_FEE_DELAY, ERR_PROTOCOL_FEE_DELAY_DECREASE, ERR_PROTOCOL_FEE_DELAY_TOO_BIG,
+        ERR_PROTOCOL_FEE_NOT_ANNOUNCED, ERR_PROTOCOL_FEE_TOO_BIG, ERR_PROTOCOL_FEE_ZERO,
+        ERR_SWAP_ADAPTER_NOT_FOUND,
+    },
+    storage::{
+        BrokerConfig, PendingProtocolFee, PendingProtocolFeeDelay, SwapAdapter, SwapAdapterType,
+        TreasuryShare,
+    },
+};
 
 multiversx_sc::imports!();
 
-#[multiversx_sc::module]
-pub trait OwnerModule: crate::storage::StorageModule {
+const MAX_PERCENTAGE: u64 = 100_00;
+const MAX_PROTOCOL_FEE: u64 = 10_00;
+const MAX_PROTOCOL_FEE_DELAY: u64 = 30 * 24 * 60 * 60;
 
+#[multiversx_sc::module]
+pub trait OwnerModule: crate::storage::StorageModule + crate::events::EventsModule {
     // Protocol Fee
+    /// Sets the protocol fee for a token (100_00 = 100%)
+    /// When a protocol fee delay is configured, fee increases need to be announced first
     #[only_owner]
     #[endpoint(setProtocolFee)]
-    fn set_protocol_fee(
+    fn set_protocol_fee(&self, token: EgldOrEsdtTokenIdentifier, value: BigUint) {
+        self.require_valid_protocol_fee(&value);
+        require!(
+            self.protocol_fee_delay().get() == 0 || value <= self.protocol_fee(&token).get(),
+            ERR_PROTOCOL_FEE_NOT_ANNOUNCED
+        );
+
+        self.clear_pending_protocol_fee(&token);
+        self.protocol_fee(&token).set(&value);
+
+        self.protocol_fee_event(&token, &value);
+    }
+
+    #[only_owner]
+    #[endpoint(removeProtocolFee)]
+    fn remove_protocol_fee(&self, token: EgldOrEsdtTokenIdentifier) {
+        self.clear_pending_protocol_fee(&token);
+        self.protocol_fee(&token).clear();
+
+        self.protocol_fee_event(&token, &BigUint::zero());
+    }
+
+    /// Announces a protocol fee change that can be applied after the protocol fee delay
+    #[only_owner]
+    #[endpoint(announceProtocolFee)]
+    fn announce_protocol_fee(&self, token: EgldOrEsdtTokenIdentifier, value: BigUint) {
+        self.require_valid_protocol_fee(&value);
+
+        let current_time = self.blockchain().get_block_timestamp();
+        let apply_after = match current_time.checked_add(self.protocol_fee_delay().get()) {
+            Some(apply_after) => apply_after,
+            None => sc_panic!(ERR_PROTOCOL_FEE_DELAY_TOO_BIG),
+        };
+        self.pending_protocol_fee(&token).set(PendingProtocolFee {
+            value: value.clone(),
+            apply_after,
+        });
+
+        self.announce_protocol_fee_event(&token, &value, apply_after);
+    }
+
+    #[only_owner]
+    #[endpoint(applyProtocolFee)]
+    fn apply_protocol_fee(&self, token: EgldOrEsdtTokenIdentifier) {
+        let pending_fee_mapper = self.pending_protocol_fee(&token);
+        require!(!pending_fee_mapper.is_empty(), ERR_NO_PENDING_PROTOCOL_FEE);
+
+        let pending_fee = pending_fee_mapper.take();
+        require!(
+            self.blockchain().get_block_timestamp() >= pending_fee.apply_after,
+            ERR_PROTOCOL_FEE_DELAY
+        );
+
+        self.protocol_fee(&token).set(&pending_fee.value);
+
+        self.protocol_fee_event(&token, &pending_fee.value);
+    }
+
+    #[only_owner]
+    #[endpoint(cancelProtocolFee)]
+    fn cancel_protocol_fee(&self, token: EgldOrEsdtTokenIdentifier) {
+        let pending_fee_mapper = self.pending_protocol_fee(&token);
+        require!(!pending_fee_mapper.is_empty(), ERR_NO_PENDING_PROTOCOL_FEE);
+
+        pending_fee_mapper.clear();
+
+        self.cancel_protocol_fee_event(&token);
+    }
+
+    /// Sets the delay (in seconds) between announcing and applying a protocol fee increase
+    /// Only increases take effect right away, a decrease has to be announced with announceProtocolFeeDelay
+    /// so a fee increase can't skip the delay users rely on
+    /// It is capped at 30 days so a mistaken value stays bounded
+    #[only_owner]
+    #[endpoint(setProtocolFeeDelay)]
+    fn set_protocol_fee_delay(&self, delay: u64) {
+        require!(
+            delay >= self.protocol_fee_delay().get(),
+            ERR_PROTOCOL_FEE_DELAY_DECREASE
+        );
+        require!(
+            delay <= MAX_PROTOCOL_FEE_DELAY,
+            ERR_PROTOCOL_FEE_DELAY_TOO_BIG
+        );
+
+        self.clear_pending_protocol_fee_delay();
+        self.protocol_fee_delay().set(delay);
+
+        self.protocol_fee_delay_event(delay);
+    }
+
+    /// Announces a protocol fee delay change that can be applied after the current protocol fee delay
+    #[only_owner]
+    #[endpoint(announceProtocolFeeDelay)]
+    fn announce_protocol_fee_delay(&self, delay: u64) {
+        require!(
+            delay <= MAX_PROTOCOL_FEE_DELAY,
+            ERR_PROTOCOL_FEE_DELAY_TOO_BIG
+        );
+
+        let current_time = self.blockchain().get_block_timestamp();
+        let apply_after = match current_time.checked_add(self.protocol_fee_delay().get()) {
+            Some(apply_after) => apply_after,
+            None => sc_panic!(ERR_PROTOCOL_FEE_DELAY_TOO_BIG),
+        };
+        self.pending_protocol_fee_delay()
+            .set(PendingProtocolFeeDelay {
+                value: delay,
+                apply_after,
+            });
+
+        self.announce_protocol_fee_delay_event(delay, apply_after);
+    }
+
+    #[only_owner]
+    #[endpoint(applyProtocolFeeDelay)]
+    fn apply_protocol_fee_delay(&self) {
+        let pending_delay_mapper = self.pending_protocol_fee_delay();
+        require!(
+            !pending_delay_mapper.is_empty(),
+            ERR_NO_PENDING_PROTOCOL_FEE_DELAY
+        );
+
+        let pending_delay = pending_delay_mapper.take();
+        require!(
+            self.blockchain().get_block_timestamp() >= pending_delay.apply_after,
+            ERR_PROTOCOL_FEE_DELAY
+        );
+
+        self.protocol_fee_delay().set(pending_delay.value);
+
+        self.protocol_fee_delay_event(pending_delay.value);
+    }
+
+    #[only_owner]
+    #[endpoint(cancelProtocolFeeDelay)]
+    fn cancel_protocol_fee_delay(&self) {
+        let pending_delay_mapper = self.pending_protocol_fee_delay();
+        require!(
+            !pending_delay_mapper.is_empty(),
+            ERR_NO_PENDING_PROTOCOL_FEE_DELAY
+        );
+
+        pending_delay_mapper.clear();
+
+        self.cancel_protocol_fee_delay_event();
+    }
+
+    /// Drops the pending protocol fee delay change, if any
+    fn clear_pending_protocol_fee_delay(&self) {
+        let pending_delay_mapper = self.pending_protocol_fee_delay();
+        if pending_delay_mapper.is_empty() {
+            return;
+        }
+
+        pending_delay_mapper.clear();
+
+        self.cancel_protocol_fee_delay_event();
+    }
+
+    /// Drops the pending protocol fee change of the token, if any
+    fn clear_pending_protocol_fee(&self, token: &EgldOrEsdtTokenIdentifier) {
+        let pending_fee_mapper = self.pending_protocol_fee(token);
+        if pending_fee_mapper.is_empty() {
+            return;
+        }
+
+        pending_fee_mapper.clear();
+
+        self.cancel_protocol_fee_event(token);
+    }
+
+    fn require_valid_protocol_fee(&self, value: &BigUint) {
+        require!(value > &BigUint::zero(), ERR_PROTOCOL_FEE_ZERO);
+        require!(
+            value <= &BigUint::from(MAX_PROTOCOL_FEE),
+            ERR_PROTOCOL_FEE_TOO_BIG
+        );
+    }
+
+    // Broker Registry
+    /// Registers a broker or updates its fee cap and default fee (100_00 = 100%)
+    /// The default fee is used when the broker is passed with a zero fee
+    #[only_owner]
+    #[endpoint(setBroker)]
+    fn set_broker(
         &self,
-        token: EgldOrEsdtTokenIdentifier,
-        value: BigUint
+        address: ManagedAddress,
+        max_fee: BigUint,
+        default_fee_opt: OptionalValue<BigUint>,
     ) {
-        require!(value > BigUint::zero(), ERR_PROTOCOL_FEE_ZERO);
-        self.protocol_fee(&token).set(value);
+        require!(max_fee <= BigUint::from(MAX_FEE), ERR_BROKER_FEE_TOO_BIG);
+
+        let default_fee = default_fee_opt.into_option();
+        if let Some(fee) = &default_fee {
+            require!(fee <= &max_fee, ERR_BROKER_FEE_ABOVE_CAP);
+        }
+
+        let broker_config_mapper = self.broker_config(&address);
+        let is_enabled = broker_config_mapper.is_empty() || broker_config_mapper.get().is_enabled;
+        let broker_config = BrokerConfig {
+            max_fee,
+            default_fee,
+            is_enabled,
+        };
+        broker_config_mapper.set(&broker_config);
+
+        self.broker_config_event(&address, &broker_config);
     }
 
     #[only_owner]
-    #[endpoint(removeProtocolFee)]
-    fn remove_protocol_fee(
+    #[endpoint(setBrokerEnabled)]
+    fn set_broker_enabled(&self, address: ManagedAddress, is_enabled: bool) {
+        let broker_config_mapper = self.broker_config(&address);
+        require!(!broker_config_mapper.is_empty(), ERR_BROKER_NOT_REGISTERED);
+
+        let mut broker_config = broker_config_mapper.get();
+        broker_config.is_enabled = is_enabled;
+        broker_config_mapper.set(&broker_config);
+
+        self.broker_config_event(&address, &broker_config);
+    }
+
+    #[only_owner]
+    #[endpoint(removeBroker)]
+    fn remove_broker(&self, address: ManagedAddress) {
+        let broker_config_mapper = self.broker_config(&address);
+        require!(!broker_config_mapper.is_empty(), ERR_BROKER_NOT_REGISTERED);
+
+        broker_config_mapper.clear();
+
+        self.remove_broker_event(&address);
+    }
+
+    /// When the broker registry is enabled, only registered brokers can be used when creating streams
+    #[only_owner]
+    #[endpoint(setBrokerRegistryEnabled)]
+    fn set_broker_registry_enabled(&self, is_enabled: bool) {
+        self.broker_registry_enabled().set(is_enabled);
+
+        self.broker_registry_enabled_event(is_enabled);
+    }
+
+    // Protocol Fee Treasury
+    /// Sets the addresses that receive the withdrawn protocol fees and their shares (100_00 = 100%)
+    /// If no treasury is set, the protocol fees are withdrawn to the owner
+    #[only_owner]
+    #[endpoint(setProtocolFeeTreasury)]
+    fn set_protocol_fee_treasury(
         &self,
-        token: EgldOrEsdtTokenIdentifier
+        shares: MultiValueEncoded<MultiValue2<ManagedAddress, u64>>,
     ) {
-        self.protocol_fee(&token).clear();
+        let mut treasury = ManagedVec::new();
+        let mut total_share = 0u64;
+        for share in shares {
+            let (address, share) = share.into_tuple();
+            require!(share > 0, ERR_INVALID_TREASURY_SHARES);
+
+            total_share += share;
+            treasury.push(TreasuryShare { address, share });
+        }
+
+        require!(
+            treasury.is_empty() || total_share == MAX_PERCENTAGE,
+            ERR_INVALID_TREASURY_SHARES
+        );
+
+        self.protocol_fee_treasury().set(&treasury);
+
+        self.protocol_fee_treasury_event(&treasury);
+    }
+
+    /// Sends the accrued protocol fees to the treasury
+    /// If no tokens are specified, all the accrued protocol fees are withdrawn
+    #[only_owner]
+    #[endpoint(withdrawProtocolFees)]
+    fn withdraw_protocol_fees(
+        &self,
+        tokens: MultiValueEncoded<MultiValue2<EgldOrEsdtTokenIdentifier, u64>>,
+    ) {
+        let mut payments = ManagedVec::<Self::Api, EgldOrEsdtTokenPayment>::new();
+        if tokens.is_empty() {
+            for (token, nonce) in self.accrued_protocol_fee_tokens().iter() {
+                let amount = self.accrued_protocol_fee(&token, nonce).get();
+                payments.push(EgldOrEsdtTokenPayment::new(token, nonce, amount));
+            }
+        } else {
+            for token_nonce in tokens {
+                let (token, nonce) = token_nonce.into_tuple();
+                // The accrued amount is cleared right away so a token passed twice is rejected
+                let amount = self.accrued_protocol_fee(&token, nonce).take();
+                require!(amount > 0, ERR_NO_PROTOCOL_FEES);
+                payments.push(EgldOrEsdtTokenPayment::new(token, nonce, amount));
+            }
+        }
+
+        require!(!payments.is_empty(), ERR_NO_PROTOCOL_FEES);
+
+        for payment in &payments {
+            self.accrued_protocol_fee(&payment.token_identifier, payment.token_nonce)
+                .clear();
+            self.accrued_protocol_fee_tokens()
+                .swap_remove(&(payment.token_identifier.clone(), payment.token_nonce));
+
+            self.send_protocol_fee(&payment);
+
+            self.withdraw_protocol_fees_event(
+                &payment.token_identifier,
+                payment.token_nonce,
+                &payment.amount,
+            );
+        }
+    }
+
+    /// Splits a protocol fee payment between the treasury addresses
+    /// The rounding dust is sent to the last treasury address
+    fn send_protocol_fee(&self, payment: &EgldOrEsdtTokenPayment) {
+        let treasury = self.protocol_fee_treasury().get();
+        if treasury.is_empty() {
+            self.send().direct(
+                &self.blockchain().get_owner_address(),
+                &payment.token_identifier,
+                payment.token_nonce,
+                &payment.amount,
+            );
+            return;
+        }
+
+        let mut remaining_amount = payment.amount.clone();
+        let last_index = treasury.len() - 1;
+        for (index, treasury_share) in treasury.iter().enumerate() {
+            let share_amount = if index == last_index {
+                remaining_amount.clone()
+            } else {
+                &payment.amount * &BigUint::from(treasury_share.share)
+                    / &BigUint::from(MAX_PERCENTAGE)
+            };
+            remaining_amount -= &share_amount;
+
+            if share_amount > 0 {
+                self.send().direct(
+                    &treasury_share.address,
+                    &payment.token_identifier,
+                    payment.token_nonce,
+                    &share_amount,
+                );
+            }
+        }
+    }
+
+    // Keeper Fee
+    /// Sets the fee paid to whoever settles a stream of the token (see settleStream)
+    /// The keeper fee is paid from the accrued protocol fees, setting it to zero disables it
+    #[only_owner]
+    #[endpoint(setKeeperFee)]
+    fn set_keeper_fee(&self, token: EgldOrEsdtTokenIdentifier, fee: BigUint) {
+        if fee == 0 {
+            self.keeper_fee(&token).clear();
+        } else {
+            self.keeper_fee(&token).set(&fee);
+        }
+
+        self.keeper_fee_event(&token, &fee);
+    }
+
+    // Swap Adapters
+    /// Registers a swap adapter that can be selected by its id when claiming with a swap
+    /// (see claimFromStreamSwapWithAdapter)
+    #[only_owner]
+    #[endpoint(addSwapAdapter)]
+    fn add_swap_adapter(&self, adapter_type: SwapAdapterType, address: ManagedAddress) -> u64 {
+        let adapter_id = self.last_swap_adapter_id().update(|x| {
+            *x += 1;
+            *x
+        });
+
+        let adapter = SwapAdapter {
+            adapter_type,
+            address,
+        };
+        self.swap_adapter(adapter_id).set(&adapter);
+
+        self.swap_adapter_event(adapter_id, &adapter);
+
+        adapter_id
+    }
+
+    #[only_owner]
+    #[endpoint(updateSwapAdapter)]
+    fn update_swap_adapter(&self, adapter_id: u64, address: ManagedAddress) {
+        let adapter_mapper = self.swap_adapter(adapter_id);
+        require!(!adapter_mapper.is_empty(), ERR_SWAP_ADAPTER_NOT_FOUND);
+
+        let mut adapter = adapter_mapper.get();
+        adapter.address = address;
+        adapter_mapper.set(&adapter);
+
+        self.swap_adapter_event(adapter_id, &adapter);
+    }
+
+    #[only_owner]
+    #[endpoint(removeSwapAdapter)]
+    fn remove_swap_adapter(&self, adapter_id: u64) {
+        let adapter_mapper = self.swap_adapter(adapter_id);
+        require!(!adapter_mapper.is_empty(), ERR_SWAP_ADAPTER_NOT_FOUND);
+
+        adapter_mapper.clear();
+
+        self.remove_swap_adapter_event(adapter_id);
+    }
+
+    // Claim And Call Whitelist
+    /// Allows the claimed tokens to be forwarded to an endpoint of a contract (see claimFromStreamAndCall)
+    /// The return tokens are the fungible tokens the endpoint can send back (e.g. receipt tokens),
+    /// they can be replaced later with setCallReturnTokens
+    #[only_owner]
+    #[endpoint(whitelistCallEndpoint)]
+    fn whitelist_call_endpoint(
+        &self,
+        target: ManagedAddress,
+        endpoint: ManagedBuffer,
+        return_tokens: MultiValueEncoded<TokenIdentifier>,
+    ) {
+        require!(
+            self.whitelisted_call_endpoints(&target)
+                .insert(endpoint.clone()),
+            ERR_CALL_ALREADY_WHITELISTED
+        );
+
+        let return_tokens = return_tokens.to_vec();
+        let mut return_tokens_mapper = self.call_return_tokens(&target, &endpoint);
+        for return_token in &return_tokens {
+            return_tokens_mapper.insert(return_token);
+        }
+
+        self.whitelist_call_endpoint_event(&target, &endpoint, &return_tokens);
+    }
+
+    /// Replaces the return tokens of a whitelisted call endpoint
+    #[only_owner]
+    #[endpoint(setCallReturnTokens)]
+    fn set_call_return_tokens(
+        &self,
+        target: ManagedAddress,
+        endpoint: ManagedBuffer,
+        return_tokens: MultiValueEncoded<TokenIdentifier>,
+    ) {
+        require!(
+            self.whitelisted_call_endpoints(&target).contains(&endpoint),
+            ERR_CALL_NOT_WHITELISTED
+        );
+
+        let return_tokens = return_tokens.to_vec();
+        let mut return_tokens_mapper = self.call_return_tokens(&target, &endpoint);
+        return_tokens_mapper.clear();
+        for return_token in &return_tokens {
+            return_tokens_mapper.insert(return_token);
+        }
+
+        self.call_return_tokens_event(&target, &endpoint, &return_tokens);
+    }
+
+    #[only_owner]
+    #[endpoint(removeCallEndpoint)]
+    fn remove_call_endpoint(&self, target: ManagedAddress, endpoint: ManagedBuffer) {
+        require!(
+            self.whitelisted_call_endpoints(&target)
+                .swap_remove(&endpoint),
+            ERR_CALL_NOT_WHITELISTED
+        );
+        self.call_return_tokens(&target, &endpoint).clear();
+
+        self.remove_call_endpoint_event(&target, &endpoint);
+    }
+
+    /// Sets the AshSwap Aggregator used by claimFromStreamSwap and createStreamSwap
+    #[only_owner]
+    #[endpoint(setAshAggregatorSc)]
+    fn set_ash_aggregator_sc(&self, address: ManagedAddress) {
+        self.ash_aggregator_sc().set(&address);
+
+        self.ash_aggregator_sc_event(&address);
+    }
+
+    #[only_owner]
+    #[endpoint(setWrapEgld)]
+    fn set_wrap_egld(&self, wrap_egld_sc: ManagedAddress, wrap_egld_token: TokenIdentifier) {
+        self.wrap_egld_sc().set(&wrap_egld_sc);
+        self.wrap_egld_token().set(&wrap_egld_token);
+
+        self.wrap_egld_event(&wrap_egld_sc, &wrap_egld_token);
     }
 }
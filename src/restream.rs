@@ -0,0 +1,64 @@
+use crate::storage::{BrokerFee, Segment};
+
+multiversx_sc::imports!();
+
+#[multiversx_sc::module]
+pub trait RestreamModule:
+    crate::storage::StorageModule
+    + crate::events::EventsModule
+    + crate::claim::ClaimModule
+    + crate::create_stream::CreateStreamModule
+    + crate::status::StatusModule
+    + crate::stream_nft::StreamNftModule
+    + crate::swap::SwapModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+{
+    /// This endpoint can be used by the recipient of a stream to claim its available balance
+    /// and use it as the deposit of a new stream, without the tokens leaving the contract
+    /// The fees are applied as for createStream and the caller is the sender of the new stream
+    /// The segments amounts are rescaled proportionally to the actual deposit, the rounding dust going to the last segment
+    #[allow_multiple_var_args]
+    #[payable("*")]
+    #[endpoint(restream)]
+    fn restream(
+        &self,
+        stream_id: u64,
+        recipient: ManagedAddress,
+        start_time: u64,
+        segments: ManagedVec<Segment<Self::Api>>,
+        cliff_opt: OptionalValue<u64>,
+        can_cancel_opt: OptionalValue<bool>,
+        brokers_opt: OptionalValue<ManagedVec<BrokerFee<Self::Api>>>,
+    ) -> u64 {
+        let caller = self.blockchain().get_caller();
+        let sc_address = self.blockchain().get_sc_address();
+
+        let payment = self.claim_from_stream_internal(stream_id, &sc_address, None);
+
+        let brokers = brokers_opt.into_option().unwrap_or_default();
+
+        let mut quote = self.new_stream_quote();
+        if let Err(error) = self.try_quote_fees(
+            &mut quote,
+            &payment.token_identifier,
+            &payment.amount,
+            &brokers,
+        ) {
+            sc_panic!(error);
+        }
+        let segments = self.rescale_segments(&segments, &quote.deposit);
+
+        self.create_stream_internal(
+            &caller,
+            Some(recipient),
+            payment,
+            start_time,
+            segments,
+            cliff_opt.into_option().unwrap_or_default(),
+            BigUint::zero(),
+            can_cancel_opt.into_option().unwrap_or(true),
+            &brokers,
+            None,
+        )
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{
+    errors::{ERR_CANT_SETTLE, ERR_STREAM_NFT_NOT_IN_CUSTODY},
+    storage::Status,
+};
+
+multiversx_sc::imports!();
+
+#[multiversx_sc::module]
+pub trait SettleStreamModule:
+    crate::storage::StorageModule
+    + crate::events::EventsModule
+    + crate::claim::ClaimModule
+    + crate::status::StatusModule
+    + crate::stream_nft::StreamNftModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+{
+    /// This endpoint can be called by anyone after the end of a stream to send the remaining balance to its recipient
+    /// Only streams with the NFT in the custody of the contract (see depositStreamNft) can be settled:
+    /// the holder of record is the recipient and the NFT is burned with the stream.
+    /// The holder of an NFT outside of the contract can't be known, so those streams have to be claimed
+    /// The caller is rewarded with the keeper fee of the stream token, paid from the accrued protocol fees
+    /// and bounded by the protocol fee paid for this stream
+    #[endpoint(settleStream)]
+    fn settle_stream(&self, stream_id: u64) {
+        let stream = self.get_stream(stream_id);
+        require!(
+            self.status_of(stream_id) == Status::Settled,
+            ERR_CANT_SETTLE
+        );
+
+        let holder_mapper = self.stream_holder(stream_id);
+        require!(!holder_mapper.is_empty(), ERR_STREAM_NFT_NOT_IN_CUSTODY);
+        let holder = holder_mapper.get();
+
+        let destination = self.get_claim_destination(stream_id, &holder);
+        let amount = &stream.deposit - &stream.claimed_amount;
+        if amount > 0 {
+            self.send().direct(
+                &destination,
+                &stream.payment_token,
+                stream.payment_nonce,
+                &amount,
+            );
+            self.claim_from_stream_event(stream_id, &amount, &holder, &destination);
+        }
+
+        let caller = self.blockchain().get_caller();
+        let keeper_fee =
+            self.take_keeper_fee(stream_id, &stream.payment_token, stream.payment_nonce);
+        if keeper_fee > 0 {
+            self.send().direct(
+                &caller,
+                &stream.payment_token,
+                stream.payment_nonce,
+                &keeper_fee,
+            );
+        }
+
+        self.settle_stream_event(stream_id, &caller, &keeper_fee);
+
+        self.remove_stream(stream_id, true);
+    }
+
+    /// Takes the keeper fee from the accrued protocol fees
+    /// The keeper fee can't exceed the protocol fee paid for the stream nor the accrued protocol fees
+    fn take_keeper_fee(
+        &self,
+        stream_id: u64,
+        token: &EgldOrEsdtTokenIdentifier,
+        nonce: u64,
+    ) -> BigUint {
+        let keeper_fee_mapper = self.keeper_fee(token);
+        if keeper_fee_mapper.is_empty() {
+            return BigUint::zero();
+        }
+
+        let accrued_fee_mapper = self.accrued_protocol_fee(token, nonce);
+        let accrued_fee = accrued_fee_mapper.get();
+        let keeper_fee = keeper_fee_mapper
+            .get()
+            .min(self.stream_protocol_fee(stream_id).get())
+            .min(accrued_fee.clone());
+        if keeper_fee == 0 {
+            return keeper_fee;
+        }
+        if keeper_fee == accrued_fee {
+            accrued_fee_mapper.clear();
+            self.accrued_protocol_fee_tokens()
+                .swap_remove(&(token.clone(), nonce));
+        } else {
+            accrued_fee_mapper.set(&accrued_fee - &keeper_fee);
+        }
+
+        keeper_fee
+    }
+}
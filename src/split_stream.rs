@@ -0,0 +1,196 @@
+use crate::{
+    errors::{
+        ERR_CANT_SPLIT, ERR_INVALID_SPLIT_SHARES, ERR_SPLIT_ABOVE_STREAMED, ERR_ZERO_DEPOSIT,
+    },
+    storage::{Segment, Status, Stream, StreamRole},
+};
+
+multiversx_sc::imports!();
+
+pub const MAX_SPLIT_SHARES: usize = 10;
+
+#[multiversx_sc::module]
+pub trait SplitStreamModule:
+    crate::storage::StorageModule
+    + crate::events::EventsModule
+    + crate::claim::ClaimModule
+    + crate::status::StatusModule
+    + crate::stream_nft::StreamNftModule
+    + crate::swap::SwapModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+{
+    /// This endpoint can be used by the recipient of a stream to split it into multiple streams
+    /// The stream NFT is burned and a new stream NFT is sent to the caller for each share
+    /// The cliff amount, every segment amount and the protocol fee paid are divided proportionally to the shares,
+    /// the rounding dust going to the last share, and the deposit of each new stream is the sum of its parts
+    /// The timing, the cancelability, the sender and the claim destination of the caller are preserved
+    /// The claimed amount is divided proportionally as well, capped by what each new stream has streamed so far;
+    /// the split is rejected if the last stream would still have more claimed than streamed
+    /// Streams with the NFT in custody can't be split, since the stream NFT has to be sent
+    #[payable("*")]
+    #[endpoint(splitStream)]
+    fn split_stream(
+        &self,
+        stream_id: u64,
+        shares: MultiValueEncoded<u64>,
+    ) -> MultiValueEncoded<u64> {
+        let (_, stream) =
+            self.require_valid_stream_nft(stream_id, OptionalValue::Some(StreamRole::Recipient));
+
+        let status = self.status_of(stream_id);
+        require!(
+            status != Status::Canceled
+                && status != Status::AwaitingAcceptance
+                && status != Status::AwaitingRedeem,
+            ERR_CANT_SPLIT
+        );
+
+        let shares = shares.to_vec();
+        require!(
+            shares.len() >= 2 && shares.len() <= MAX_SPLIT_SHARES,
+            ERR_INVALID_SPLIT_SHARES
+        );
+        let mut total_shares = BigUint::zero();
+        for share in shares.iter() {
+            require!(share > 0, ERR_INVALID_SPLIT_SHARES);
+            total_shares += BigUint::from(share);
+        }
+
+        let cliff_amount_parts =
+            self.split_proportionally(&stream.cliff_amount, &shares, &total_shares);
+        let claimed_parts =
+            self.split_proportionally(&stream.claimed_amount, &shares, &total_shares);
+        let protocol_fee_parts = self.split_proportionally(
+            &self.stream_protocol_fee(stream_id).get(),
+            &shares,
+            &total_shares,
+        );
+        let mut segment_parts = ManagedVec::<Self::Api, ManagedVec<BigUint>>::new();
+        for segment in &stream.segments {
+            segment_parts.push(self.split_proportionally(&segment.amount, &shares, &total_shares));
+        }
+
+        let caller = self.blockchain().get_caller();
+        let claim_destination_mapper = self.claim_destination(stream_id);
+        let claim_destination_opt = if !claim_destination_mapper.is_empty()
+            && claim_destination_mapper.get().holder == caller
+        {
+            Some(claim_destination_mapper.get())
+        } else {
+            None
+        };
+
+        let last_index = shares.len() - 1;
+        let mut remaining_claimed_amount = stream.claimed_amount.clone();
+        let mut new_stream_ids = ManagedVec::<Self::Api, u64>::new();
+        for index in 0..shares.len() {
+            let cliff_amount = cliff_amount_parts.get(index).clone_value();
+            let mut deposit = cliff_amount.clone();
+            let mut segments = ManagedVec::new();
+            for (segment_index, segment) in stream.segments.iter().enumerate() {
+                let amount = segment_parts.get(segment_index).get(index).clone_value();
+                deposit += &amount;
+                segments.push(Segment { amount, ..segment });
+            }
+            require!(deposit > 0, ERR_ZERO_DEPOSIT);
+
+            let mut new_stream = Stream {
+                sender: stream.sender.clone(),
+                nft_nonce: 0,
+                payment_token: stream.payment_token.clone(),
+                payment_nonce: stream.payment_nonce,
+                deposit,
+                claimed_amount: BigUint::zero(),
+                can_cancel: stream.can_cancel,
+                start_time: stream.start_time,
+                end_time: stream.end_time,
+                cliff: stream.cliff,
+                cliff_amount,
+                segments,
+                balances_after_cancel: None,
+                acceptance_deadline: None,
+            };
+
+            // The rounding of the parts can leave a new stream with less streamed than its claimed share
+            let streamed_amount = self.get_streamed_amount(&new_stream);
+            new_stream.claimed_amount = if index == last_index {
+                require!(
+                    remaining_claimed_amount <= streamed_amount,
+                    ERR_SPLIT_ABOVE_STREAMED
+                );
+                remaining_claimed_amount.clone()
+            } else {
+                claimed_parts.get(index).clone_value().min(streamed_amount)
+            };
+            remaining_claimed_amount -= &new_stream.claimed_amount;
+
+            let new_stream_id = self.get_next_stream_id();
+            new_stream.nft_nonce = self.mint_stream_nft(new_stream_id, &new_stream);
+            self.save_stream(new_stream_id, &new_stream);
+
+            let protocol_fee = protocol_fee_parts.get(index).clone_value();
+            if protocol_fee > 0 {
+                self.stream_protocol_fee(new_stream_id).set(&protocol_fee);
+            }
+            if let Some(claim_destination) = &claim_destination_opt {
+                self.claim_destination(new_stream_id).set(claim_destination);
+            }
+
+            self.send().direct_esdt(
+                &caller,
+                self.stream_nft_token().get_token_id_ref(),
+                new_stream.nft_nonce,
+                &BigUint::from(1u32),
+            );
+
+            self.create_stream_event(
+                &new_stream.sender,
+                &caller,
+                self.stream_nft_token().get_token_id_ref(),
+                new_stream.nft_nonce,
+                &new_stream.payment_token,
+                new_stream.payment_nonce,
+                &new_stream.deposit,
+                &new_stream.deposit,
+                new_stream.start_time,
+                new_stream.end_time,
+                new_stream.can_cancel,
+                new_stream.cliff,
+                &new_stream.cliff_amount,
+                &new_stream.segments,
+            );
+
+            new_stream_ids.push(new_stream_id);
+        }
+
+        self.split_stream_event(stream_id, &caller, &new_stream_ids);
+
+        self.remove_stream(stream_id, true);
+
+        MultiValueEncoded::from(new_stream_ids)
+    }
+
+    /// Divides the amount proportionally to the shares, the rounding dust going to the last share
+    fn split_proportionally(
+        &self,
+        amount: &BigUint,
+        shares: &ManagedVec<u64>,
+        total_shares: &BigUint,
+    ) -> ManagedVec<BigUint> {
+        let mut parts = ManagedVec::new();
+        let mut remaining_amount = amount.clone();
+        let last_index = shares.len() - 1;
+        for (index, share) in shares.iter().enumerate() {
+            let part = if index == last_index {
+                remaining_amount.clone()
+            } else {
+                amount * &BigUint::from(share) / total_shares
+            };
+            remaining_amount -= &part;
+
+            parts.push(part);
+        }
+
+        parts
+    }
+}
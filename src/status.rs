@@ -13,12 +13,20 @@ pub trait StatusModule: crate::storage::StorageModule {
             return Status::Finished;
         }
 
-        let stream = stream_mapper.get();
+        let stream = self.get_stream(stream_id);
 
         if stream.balances_after_cancel.is_some() {
             return Status::Canceled;
         }
 
+        if stream.acceptance_deadline.is_some() {
+            if !self.stream_secret_hash(stream_id).is_empty() {
+                return Status::AwaitingRedeem;
+            }
+
+            return Status::AwaitingAcceptance;
+        }
+
         let current_time = self.blockchain().get_block_timestamp();
         if current_time < stream.start_time {
             return Status::Pending;
@@ -31,6 +39,7 @@ pub trait StatusModule: crate::storage::StorageModule {
         Status::Settled
     }
 
+    /// Streams awaiting the recipient acceptance (or redeem) are not warm: they can't be claimed, canceled or topped up
     fn is_warm(&self, stream_id: u64) -> bool {
         let stream_status = self.status_of(stream_id);
         stream_status == Status::Pending || stream_status == Status::InProgress
@@ -3,6 +3,9 @@ use crate::errors::ERR_INVALID_STREAM;
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
+/// Layout version of the streams stored with rational segment exponents, cliff amounts and acceptance deadlines
+pub const STREAM_LAYOUT_VERSION: u8 = 1;
+
 #[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, PartialEq)]
 pub enum StreamRole {
     Sender,
@@ -16,6 +19,8 @@ pub enum Status {
     Canceled,
     Settled,
     Finished,
+    AwaitingAcceptance,
+    AwaitingRedeem,
 }
 
 #[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone)]
@@ -28,6 +33,7 @@ pub struct BalancesAfterCancel<M: ManagedTypeApi> {
 pub struct Segment<M: ManagedTypeApi> {
     pub amount: BigUint<M>,
     pub exponent: u32,
+    pub exponent_denominator: u32,
     pub duration: u64,
 }
 
@@ -43,9 +49,73 @@ pub struct Stream<M: ManagedTypeApi> {
     pub start_time: u64,
     pub end_time: u64,
     pub cliff: u64,
+    pub cliff_amount: BigUint<M>,
     pub segments: ManagedVec<M, Segment<M>>,
     pub balances_after_cancel: Option<BalancesAfterCancel<M>>,
+    // For hash locked streams this is the redeem expiry (see Status::AwaitingRedeem)
+    pub acceptance_deadline: Option<u64>,
+}
+
+/**
+ * Legacy Structs
+ * Streams created before segments had rational exponents are stored with this layout
+ */
+#[derive(TopDecode, NestedDecode, ManagedVecItem, Clone)]
+pub struct LegacySegment<M: ManagedTypeApi> {
+    pub amount: BigUint<M>,
+    pub exponent: u32,
+    pub duration: u64,
+}
+
+#[derive(TopDecode)]
+pub struct LegacyStream<M: ManagedTypeApi> {
+    pub sender: ManagedAddress<M>,
+    pub nft_nonce: u64,
+    pub payment_token: EgldOrEsdtTokenIdentifier<M>,
+    pub payment_nonce: u64,
+    pub deposit: BigUint<M>,
+    pub claimed_amount: BigUint<M>,
+    pub can_cancel: bool,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub cliff: u64,
+    pub segments: ManagedVec<M, LegacySegment<M>>,
+    pub balances_after_cancel: Option<BalancesAfterCancel<M>>,
+}
+
+impl<M: ManagedTypeApi> From<LegacyStream<M>> for Stream<M> {
+    fn from(legacy_stream: LegacyStream<M>) -> Self {
+        let mut segments = ManagedVec::new();
+        for legacy_segment in &legacy_stream.segments {
+            segments.push(Segment {
+                amount: legacy_segment.amount,
+                exponent: legacy_segment.exponent,
+                exponent_denominator: 1,
+                duration: legacy_segment.duration,
+            });
+        }
+
+        Stream {
+            sender: legacy_stream.sender,
+            nft_nonce: legacy_stream.nft_nonce,
+            payment_token: legacy_stream.payment_token,
+            payment_nonce: legacy_stream.payment_nonce,
+            deposit: legacy_stream.deposit,
+            claimed_amount: legacy_stream.claimed_amount,
+            can_cancel: legacy_stream.can_cancel,
+            start_time: legacy_stream.start_time,
+            end_time: legacy_stream.end_time,
+            cliff: legacy_stream.cliff,
+            cliff_amount: BigUint::zero(),
+            segments,
+            balances_after_cancel: legacy_stream.balances_after_cancel,
+            acceptance_deadline: None,
+        }
+    }
 }
+/**
+ * END Legacy Structs
+ */
 
 #[derive(TopEncode, TopDecode, TypeAbi, ManagedVecItem, NestedEncode, NestedDecode, Clone)]
 pub struct BrokerFee<M: ManagedTypeApi> {
@@ -53,6 +123,59 @@ pub struct BrokerFee<M: ManagedTypeApi> {
     pub fee: BigUint<M>,
 }
 
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, ManagedVecItem, Clone)]
+pub struct StreamRequest<M: ManagedTypeApi> {
+    pub recipient: ManagedAddress<M>,
+    pub payment_token: EgldOrEsdtTokenIdentifier<M>,
+    pub payment_nonce: u64,
+    pub amount: BigUint<M>,
+    pub start_time: u64,
+    pub segments: ManagedVec<M, Segment<M>>,
+    pub cliff: u64,
+    pub cliff_amount: BigUint<M>,
+    pub can_cancel: bool,
+}
+
+#[derive(TopEncode, TopDecode, TypeAbi, Clone)]
+pub struct StreamQuote<M: ManagedTypeApi> {
+    pub protocol_fee: BigUint<M>,
+    pub broker_fee: BigUint<M>,
+    pub deposit: BigUint<M>,
+    pub end_time: u64,
+    pub error: Option<ManagedBuffer<M>>,
+}
+
+#[derive(TopEncode, TopDecode, TypeAbi, Clone)]
+pub struct PendingProtocolFee<M: ManagedTypeApi> {
+    pub value: BigUint<M>,
+    pub apply_after: u64,
+}
+
+#[derive(TopEncode, TopDecode, TypeAbi, Clone)]
+pub struct PendingProtocolFeeDelay {
+    pub value: u64,
+    pub apply_after: u64,
+}
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, ManagedVecItem, Clone)]
+pub struct TreasuryShare<M: ManagedTypeApi> {
+    pub address: ManagedAddress<M>,
+    pub share: u64,
+}
+
+#[derive(TopEncode, TopDecode, TypeAbi, Clone)]
+pub struct BrokerConfig<M: ManagedTypeApi> {
+    pub max_fee: BigUint<M>,
+    pub default_fee: Option<BigUint<M>>,
+    pub is_enabled: bool,
+}
+
+#[derive(TopEncode, TopDecode, TypeAbi, Clone)]
+pub struct ClaimDestination<M: ManagedTypeApi> {
+    pub holder: ManagedAddress<M>,
+    pub destination: ManagedAddress<M>,
+}
+
 #[derive(TopEncode, TopDecode, TypeAbi, Clone, PartialEq, Debug)]
 pub struct StreamAttributes<M: ManagedTypeApi> {
     pub sender: ManagedAddress<M>,
@@ -64,6 +187,7 @@ pub struct StreamAttributes<M: ManagedTypeApi> {
     pub start_time: u64,
     pub end_time: u64,
     pub cliff: u64,
+    pub cliff_amount: BigUint<M>,
     pub is_canceled: bool,
 }
 
@@ -92,24 +216,132 @@ pub struct AggregatorStep<M: ManagedTypeApi> {
  * END Ash Aggregator Structs
  */
 
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, PartialEq, Clone, Copy)]
+pub enum SwapAdapterType {
+    AshAggregator,
+    XExchangeRouter,
+    SinglePool,
+}
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone)]
+pub struct SwapAdapter<M: ManagedTypeApi> {
+    pub adapter_type: SwapAdapterType,
+    pub address: ManagedAddress<M>,
+}
+
 #[multiversx_sc::module]
 pub trait StorageModule {
     #[view(getStreamData)]
     fn get_stream(&self, stream_id: u64) -> Stream<Self::Api> {
         let stream_mapper = self.stream_by_id(stream_id);
         require!(!stream_mapper.is_empty(), ERR_INVALID_STREAM);
+
+        // Streams without a layout version were stored before rational segment exponents
+        if self.stream_layout_version(stream_id).is_empty() {
+            let raw_stream = self.stream_raw_by_id(stream_id).get();
+            return match LegacyStream::top_decode(raw_stream) {
+                core::result::Result::Ok(legacy_stream) => legacy_stream.into(),
+                core::result::Result::Err(err) => {
+                    sc_panic!("Stream decode error: {}", err.message_bytes())
+                }
+            };
+        }
+
         stream_mapper.get()
     }
 
+    /// Stores the stream with the current layout, so a legacy stream is migrated on its first update
+    fn save_stream(&self, stream_id: u64, stream: &Stream<Self::Api>) {
+        self.stream_by_id(stream_id).set(stream);
+
+        let layout_version_mapper = self.stream_layout_version(stream_id);
+        if layout_version_mapper.is_empty() {
+            layout_version_mapper.set(STREAM_LAYOUT_VERSION);
+        }
+    }
+
+    #[view(getLastStreamId)]
     fn get_last_stream_id(&self) -> u64 {
-        self.blockchain().get_current_esdt_nft_nonce(
-            &self.blockchain().get_sc_address(),
-            self.stream_nft_token().get_token_id_ref(),
-        )
+        // Before the stream id counter was introduced, stream ids matched the stream NFT nonces
+        if self.last_stream_id().is_empty() {
+            return self.blockchain().get_current_esdt_nft_nonce(
+                &self.blockchain().get_sc_address(),
+                self.stream_nft_token().get_token_id_ref(),
+            );
+        }
+
+        self.last_stream_id().get()
+    }
+
+    fn get_next_stream_id(&self) -> u64 {
+        let stream_id = self.get_last_stream_id() + 1;
+        self.last_stream_id().set(stream_id);
+
+        stream_id
+    }
+
+    #[view(getStreamIdByNftNonce)]
+    fn get_stream_id_by_nft_nonce(&self, nft_nonce: u64) -> u64 {
+        let stream_id_mapper = self.stream_id_by_nft_nonce(nft_nonce);
+        // Streams created before the stream id counter have the same id as the NFT nonce
+        if stream_id_mapper.is_empty() {
+            return nft_nonce;
+        }
+
+        stream_id_mapper.get()
     }
 
     #[storage_mapper("streamById")]
     fn stream_by_id(&self, stream_id: u64) -> SingleValueMapper<Stream<Self::Api>>;
+    #[storage_mapper("streamById")]
+    fn stream_raw_by_id(&self, stream_id: u64) -> SingleValueMapper<ManagedBuffer>;
+    #[storage_mapper("streamLayoutVersion")]
+    fn stream_layout_version(&self, stream_id: u64) -> SingleValueMapper<u8>;
+
+    #[storage_mapper("lastStreamId")]
+    fn last_stream_id(&self) -> SingleValueMapper<u64>;
+    #[storage_mapper("streamIdByNftNonce")]
+    fn stream_id_by_nft_nonce(&self, nft_nonce: u64) -> SingleValueMapper<u64>;
+
+    #[view(getStreamSecretHash)]
+    #[storage_mapper("streamSecretHash")]
+    fn stream_secret_hash(
+        &self,
+        stream_id: u64,
+    ) -> SingleValueMapper<ManagedByteArray<Self::Api, 32>>;
+
+    /// The block nonce of each redeem commitment of a hash locked stream
+    #[view(getRedeemCommitment)]
+    #[storage_mapper("redeemCommitment")]
+    fn redeem_commitment(
+        &self,
+        stream_id: u64,
+        commitment: &ManagedByteArray<Self::Api, 32>,
+    ) -> SingleValueMapper<u64>;
+
+    // The redeem commitments of a hash locked stream, kept so they can be cleared
+    #[storage_mapper("redeemCommitments")]
+    fn redeem_commitments(
+        &self,
+        stream_id: u64,
+    ) -> UnorderedSetMapper<ManagedByteArray<Self::Api, 32>>;
+
+    #[view(getClaimDestination)]
+    #[storage_mapper("claimDestination")]
+    fn claim_destination(&self, stream_id: u64) -> SingleValueMapper<ClaimDestination<Self::Api>>;
+
+    // Stream NFTs in the custody of the contract
+    #[view(getStreamHolder)]
+    #[storage_mapper("streamHolder")]
+    fn stream_holder(&self, stream_id: u64) -> SingleValueMapper<ManagedAddress>;
+    #[view(getStreamClaimOperators)]
+    #[storage_mapper("streamClaimOperators")]
+    fn stream_claim_operators(&self, stream_id: u64) -> UnorderedSetMapper<ManagedAddress>;
+
+    fn clear_stream_custody(&self, stream_id: u64) {
+        self.stream_holder(stream_id).clear();
+        self.stream_claim_operators(stream_id).clear();
+    }
 
     #[storage_mapper("streamNftToken")]
     fn stream_nft_token(&self) -> NonFungibleTokenMapper<Self::Api>;
@@ -121,6 +353,81 @@ pub trait StorageModule {
     #[storage_mapper("protocolFee")]
     fn protocol_fee(&self, token: &EgldOrEsdtTokenIdentifier) -> SingleValueMapper<BigUint>;
 
+    #[view(getPendingProtocolFee)]
+    #[storage_mapper("pendingProtocolFee")]
+    fn pending_protocol_fee(
+        &self,
+        token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<PendingProtocolFee<Self::Api>>;
+
+    #[view(getProtocolFeeDelay)]
+    #[storage_mapper("protocolFeeDelay")]
+    fn protocol_fee_delay(&self) -> SingleValueMapper<u64>;
+
+    #[view(getPendingProtocolFeeDelay)]
+    #[storage_mapper("pendingProtocolFeeDelay")]
+    fn pending_protocol_fee_delay(&self) -> SingleValueMapper<PendingProtocolFeeDelay>;
+
+    fn accrue_protocol_fee(&self, token: &EgldOrEsdtTokenIdentifier, nonce: u64, amount: &BigUint) {
+        if *amount == 0 {
+            return;
+        }
+
+        self.accrued_protocol_fee(token, nonce)
+            .update(|accrued_fee| *accrued_fee += amount);
+        self.accrued_protocol_fee_tokens()
+            .insert((token.clone(), nonce));
+    }
+
+    #[view(getAccruedProtocolFees)]
+    fn get_accrued_protocol_fees(&self) -> MultiValueEncoded<EgldOrEsdtTokenPayment> {
+        let mut accrued_fees = MultiValueEncoded::new();
+        for (token, nonce) in self.accrued_protocol_fee_tokens().iter() {
+            let amount = self.accrued_protocol_fee(&token, nonce).get();
+            accrued_fees.push(EgldOrEsdtTokenPayment::new(token, nonce, amount));
+        }
+
+        accrued_fees
+    }
+
+    #[view(getAccruedProtocolFee)]
+    #[storage_mapper("accruedProtocolFee")]
+    fn accrued_protocol_fee(
+        &self,
+        token: &EgldOrEsdtTokenIdentifier,
+        nonce: u64,
+    ) -> SingleValueMapper<BigUint>;
+    #[storage_mapper("accruedProtocolFeeTokens")]
+    fn accrued_protocol_fee_tokens(&self) -> UnorderedSetMapper<(EgldOrEsdtTokenIdentifier, u64)>;
+
+    #[view(getKeeperFee)]
+    #[storage_mapper("keeperFee")]
+    fn keeper_fee(&self, token: &EgldOrEsdtTokenIdentifier) -> SingleValueMapper<BigUint>;
+    /// The protocol fee paid on the deposits of a stream, which bounds the keeper fee of its settlement
+    #[view(getStreamProtocolFee)]
+    #[storage_mapper("streamProtocolFee")]
+    fn stream_protocol_fee(&self, stream_id: u64) -> SingleValueMapper<BigUint>;
+
+    #[view(getProtocolFeeTreasury)]
+    #[storage_mapper("protocolFeeTreasury")]
+    fn protocol_fee_treasury(&self) -> SingleValueMapper<ManagedVec<TreasuryShare<Self::Api>>>;
+
+    // Brokers
+    #[view(isBrokerRegistryEnabled)]
+    #[storage_mapper("brokerRegistryEnabled")]
+    fn broker_registry_enabled(&self) -> SingleValueMapper<bool>;
+    #[view(getBrokerConfig)]
+    #[storage_mapper("brokerConfig")]
+    fn broker_config(&self, address: &ManagedAddress)
+        -> SingleValueMapper<BrokerConfig<Self::Api>>;
+    #[view(getBrokerFeeVolume)]
+    #[storage_mapper("brokerFeeVolume")]
+    fn broker_fee_volume(
+        &self,
+        address: &ManagedAddress,
+        token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<BigUint>;
+
     // Ash Aggregator
     #[view(getAshAggregatorSc)]
     #[storage_mapper("ashAggregatorSc")]
@@ -131,4 +438,28 @@ pub trait StorageModule {
     #[view(getWrapEgldToken)]
     #[storage_mapper("wrapEgldToken")]
     fn wrap_egld_token(&self) -> SingleValueMapper<TokenIdentifier>;
+
+    // Swap Adapters
+    #[view(getLastSwapAdapterId)]
+    #[storage_mapper("lastSwapAdapterId")]
+    fn last_swap_adapter_id(&self) -> SingleValueMapper<u64>;
+    #[view(getSwapAdapter)]
+    #[storage_mapper("swapAdapter")]
+    fn swap_adapter(&self, adapter_id: u64) -> SingleValueMapper<SwapAdapter<Self::Api>>;
+
+    // Claim And Call Whitelist
+    #[view(getWhitelistedCallEndpoints)]
+    #[storage_mapper("whitelistedCallEndpoints")]
+    fn whitelisted_call_endpoints(
+        &self,
+        target: &ManagedAddress,
+    ) -> UnorderedSetMapper<ManagedBuffer>;
+    /// The fungible tokens a whitelisted endpoint can send back, which are forwarded to the claimer
+    #[view(getCallReturnTokens)]
+    #[storage_mapper("callReturnTokens")]
+    fn call_return_tokens(
+        &self,
+        target: &ManagedAddress,
+        endpoint: &ManagedBuffer,
+    ) -> UnorderedSetMapper<TokenIdentifier>;
 }
@@ -40,13 +40,13 @@ pub trait StreamNftModule:
         );
     }
 
-    fn mint_stream_nft(&self, stream: &Stream<Self::Api>) -> u64 {
+    fn mint_stream_nft(&self, stream_id: u64, stream: &Stream<Self::Api>) -> u64 {
         require!(!self.stream_nft_token().is_empty(), ERR_TOKEN_NOT_ISSUED);
 
         let big_one = BigUint::from(1u64);
 
         let mut token_name = ManagedBuffer::new_from_bytes(b"CoinDrip Stream #");
-        let stream_id_buffer = self.u64_to_ascii(stream.nft_nonce);
+        let stream_id_buffer = self.u64_to_ascii(stream_id);
         token_name.append(&stream_id_buffer);
 
         let mut uris = ManagedVec::new();
@@ -59,18 +59,7 @@ pub trait StreamNftModule:
 
         let royalties = BigUint::from(NFT_ROYALTIES);
 
-        let attributes = StreamAttributes {
-            sender: stream.sender.clone(),
-            payment_token: stream.payment_token.clone(),
-            payment_nonce: stream.payment_nonce,
-            deposit: stream.deposit.clone(),
-            remaining_balance: stream.deposit.clone(),
-            can_cancel: stream.can_cancel,
-            start_time: stream.start_time,
-            end_time: stream.end_time,
-            cliff: stream.cliff,
-            is_canceled: false,
-        };
+        let attributes = self.get_stream_nft_attributes(stream);
         let mut serialized_attributes = ManagedBuffer::new();
         if let core::result::Result::Err(err) = attributes.top_encode(&mut serialized_attributes) {
             sc_panic!("Attributes encode error: {}", err.message_bytes());
@@ -89,9 +78,30 @@ pub trait StreamNftModule:
             &uris,
         );
 
+        self.stream_id_by_nft_nonce(nonce).set(stream_id);
+
         nonce
     }
 
+    /**
+     * Builds the stream NFT attributes from the current stream state
+     */
+    fn get_stream_nft_attributes(&self, stream: &Stream<Self::Api>) -> StreamAttributes<Self::Api> {
+        StreamAttributes {
+            sender: stream.sender.clone(),
+            payment_token: stream.payment_token.clone(),
+            payment_nonce: stream.payment_nonce,
+            deposit: stream.deposit.clone(),
+            remaining_balance: &stream.deposit - &stream.claimed_amount,
+            can_cancel: stream.can_cancel,
+            start_time: stream.start_time,
+            end_time: stream.end_time,
+            cliff: stream.cliff,
+            cliff_amount: stream.cliff_amount.clone(),
+            is_canceled: false,
+        }
+    }
+
     /**
      * This endpoint checks if a valid stream NFTs is send or if caller is the stream sender
      */
@@ -0,0 +1,422 @@
+use crate::{
+    errors::{
+        ERR_ASH_INVALID_INPUT_AMOUNT, ERR_ASH_INVALID_INPUT_TOKEN,
+        ERR_ASH_INVALID_LIMITS_STEPS_LENGTH, ERR_ASH_INVALID_STEPS_LENGTH,
+        ERR_ASH_OUTPUT_BELOW_LIMIT, ERR_SWAP_ADAPTER_INVALID_STEPS, ERR_SWAP_ADAPTER_NOT_FOUND,
+    },
+    storage::{AggregatorStep, SwapAdapter, SwapAdapterType, TokenAmount},
+};
+
+multiversx_sc::imports!();
+
+const SWAP_TOKENS_FIXED_INPUT: &[u8] = b"swapTokensFixedInput";
+
+mod ash_aggregator {
+    use crate::storage::{AggregatorStep, TokenAmount};
+
+    multiversx_sc::imports!();
+
+    #[multiversx_sc::proxy]
+    pub trait AggregatorContract {
+        #[payable("*")]
+        #[endpoint]
+        fn aggregate(
+            &self,
+            steps: ManagedVec<AggregatorStep<Self::Api>>,
+            limits: MultiValueEncoded<TokenAmount<Self::Api>>,
+        ) -> ManagedVec<EsdtTokenPayment>;
+    }
+}
+
+mod xexchange_router {
+    multiversx_sc::imports!();
+
+    pub type SwapOperationType<M> =
+        MultiValue4<ManagedAddress<M>, ManagedBuffer<M>, TokenIdentifier<M>, BigUint<M>>;
+
+    #[multiversx_sc::proxy]
+    pub trait RouterContract {
+        #[payable("*")]
+        #[endpoint(multiPairSwap)]
+        fn multi_pair_swap(
+            &self,
+            swap_operations: MultiValueEncoded<SwapOperationType<Self::Api>>,
+        ) -> ManagedVec<EsdtTokenPayment>;
+    }
+}
+
+mod pair {
+    multiversx_sc::imports!();
+
+    #[multiversx_sc::proxy]
+    pub trait PairContract {
+        #[payable("*")]
+        #[endpoint(swapTokensFixedInput)]
+        fn swap_tokens_fixed_input(
+            &self,
+            token_out: TokenIdentifier,
+            amount_out_min: BigUint,
+        ) -> EsdtTokenPayment;
+    }
+}
+
+mod wrap_egld {
+    multiversx_sc::imports!();
+
+    #[multiversx_sc::proxy]
+    pub trait WrapEgldContract {
+        #[payable("EGLD")]
+        #[endpoint(wrapEgld)]
+        fn wrap_egld(&self) -> EsdtTokenPayment;
+
+        #[payable("*")]
+        #[endpoint(unwrapEgld)]
+        fn unwrap_egld(&self);
+    }
+}
+
+#[multiversx_sc::module]
+pub trait SwapModule: crate::storage::StorageModule + crate::events::EventsModule {
+    #[proxy]
+    fn ash_aggregator_proxy(&self, sc_address: ManagedAddress) -> ash_aggregator::Proxy<Self::Api>;
+
+    #[proxy]
+    fn xexchange_router_proxy(
+        &self,
+        sc_address: ManagedAddress,
+    ) -> xexchange_router::Proxy<Self::Api>;
+
+    #[proxy]
+    fn pair_proxy(&self, sc_address: ManagedAddress) -> pair::Proxy<Self::Api>;
+
+    #[proxy]
+    fn wrap_egld_proxy(&self, sc_address: ManagedAddress) -> wrap_egld::Proxy<Self::Api>;
+
+    fn require_valid_swap_route(
+        &self,
+        steps: &ManagedVec<AggregatorStep<Self::Api>>,
+        limits: &ManagedVec<TokenAmount<Self::Api>>,
+    ) {
+        require!(steps.len() > 0, ERR_ASH_INVALID_STEPS_LENGTH);
+        require!(limits.len() > 0, ERR_ASH_INVALID_STEPS_LENGTH);
+        require!(
+            steps.len() + 1 == limits.len(),
+            ERR_ASH_INVALID_LIMITS_STEPS_LENGTH
+        );
+    }
+
+    /// Returns the default adapter, using the AshSwap Aggregator set by the owner
+    fn get_ash_aggregator_adapter(&self) -> SwapAdapter<Self::Api> {
+        SwapAdapter {
+            adapter_type: SwapAdapterType::AshAggregator,
+            address: self.ash_aggregator_sc().get(),
+        }
+    }
+
+    fn get_swap_adapter(&self, adapter_id: u64) -> SwapAdapter<Self::Api> {
+        let adapter_mapper = self.swap_adapter(adapter_id);
+        require!(!adapter_mapper.is_empty(), ERR_SWAP_ADAPTER_NOT_FOUND);
+
+        adapter_mapper.get()
+    }
+
+    /// Checks that the swap steps can be executed by the adapter
+    /// xExchange routes must chain the steps and a single pool adapter only supports one step
+    fn require_valid_adapter_route(
+        &self,
+        adapter: &SwapAdapter<Self::Api>,
+        steps: &ManagedVec<AggregatorStep<Self::Api>>,
+    ) {
+        match adapter.adapter_type {
+            SwapAdapterType::AshAggregator => {}
+            SwapAdapterType::XExchangeRouter => {
+                for index in 1..steps.len() {
+                    require!(
+                        steps.get(index).token_in == steps.get(index - 1).token_out,
+                        ERR_SWAP_ADAPTER_INVALID_STEPS
+                    );
+                }
+            }
+            SwapAdapterType::SinglePool => {
+                require!(steps.len() == 1, ERR_SWAP_ADAPTER_INVALID_STEPS);
+            }
+        }
+    }
+
+    /// Returns the amount of the input token (the token of the first step) used by the swap route
+    fn get_swap_input_amount(&self, steps: &ManagedVec<AggregatorStep<Self::Api>>) -> BigUint {
+        let input_token = steps.get(0).token_in;
+
+        let mut input_amount = BigUint::zero();
+        for step in steps {
+            if step.token_in == input_token {
+                input_amount += step.amount_in;
+            }
+        }
+
+        input_amount
+    }
+
+    /// Validates the input of the swap route against the payment and returns the payment to send to the adapter
+    /// If payment token is EGLD we wrap the input amount before doing the swap
+    fn prepare_swap_input(
+        &self,
+        payment: &EgldOrEsdtTokenPayment,
+        steps: &ManagedVec<AggregatorStep<Self::Api>>,
+    ) -> EsdtTokenPayment {
+        let (input_token, input_nonce) = if payment.token_identifier.is_egld() {
+            (self.wrap_egld_token().get(), 0)
+        } else {
+            (
+                payment.token_identifier.clone().unwrap_esdt(),
+                payment.token_nonce,
+            )
+        };
+        require!(
+            steps.get(0).token_in == input_token,
+            ERR_ASH_INVALID_INPUT_TOKEN
+        );
+
+        let input_amount = self.get_swap_input_amount(steps);
+        require!(
+            input_amount > 0 && input_amount <= payment.amount,
+            ERR_ASH_INVALID_INPUT_AMOUNT
+        );
+
+        if payment.token_identifier.is_egld() {
+            let _: IgnoreValue = self
+                .wrap_egld_proxy(self.wrap_egld_sc().get())
+                .wrap_egld()
+                .with_egld_transfer(input_amount.clone())
+                .execute_on_dest_context();
+        }
+
+        EsdtTokenPayment::new(input_token, input_nonce, input_amount)
+    }
+
+    /// Builds the xExchange router swap operations
+    /// The limit following each step is used as the minimum output of that step
+    fn get_xexchange_swap_operations(
+        &self,
+        steps: &ManagedVec<AggregatorStep<Self::Api>>,
+        limits: &ManagedVec<TokenAmount<Self::Api>>,
+    ) -> MultiValueEncoded<xexchange_router::SwapOperationType<Self::Api>> {
+        let mut swap_operations = MultiValueEncoded::new();
+        for (index, step) in steps.iter().enumerate() {
+            swap_operations.push(
+                (
+                    step.pool_address,
+                    ManagedBuffer::new_from_bytes(SWAP_TOKENS_FIXED_INPUT),
+                    step.token_out,
+                    limits.get(index + 1).amount,
+                )
+                    .into(),
+            );
+        }
+
+        swap_operations
+    }
+
+    /// Swaps the payment using the given adapter
+    /// Only the input amount of the swap route is swapped, the rest of the payment is returned as unswapped
+    /// The output of the last token limit is checked against its minimum amount
+    /// Returns the resulting payments and the unswapped payment
+    fn swap_tokens(
+        &self,
+        adapter: &SwapAdapter<Self::Api>,
+        payment: EgldOrEsdtTokenPayment,
+        steps: ManagedVec<AggregatorStep<Self::Api>>,
+        limits: ManagedVec<TokenAmount<Self::Api>>,
+    ) -> (ManagedVec<EsdtTokenPayment>, EgldOrEsdtTokenPayment) {
+        let input_payment = self.prepare_swap_input(&payment, &steps);
+        let input_amount = input_payment.amount.clone();
+        let output_limit = limits.get(limits.len() - 1);
+
+        let result_payments = match adapter.adapter_type {
+            SwapAdapterType::AshAggregator => self
+                .ash_aggregator_proxy(adapter.address.clone())
+                .aggregate(steps, MultiValueEncoded::from(limits))
+                .with_esdt_transfer(input_payment)
+                .execute_on_dest_context::<ManagedVec<EsdtTokenPayment>>(),
+            SwapAdapterType::XExchangeRouter => self
+                .xexchange_router_proxy(adapter.address.clone())
+                .multi_pair_swap(self.get_xexchange_swap_operations(&steps, &limits))
+                .with_esdt_transfer(input_payment)
+                .execute_on_dest_context::<ManagedVec<EsdtTokenPayment>>(),
+            SwapAdapterType::SinglePool => {
+                let result_payment = self
+                    .pair_proxy(adapter.address.clone())
+                    .swap_tokens_fixed_input(steps.get(0).token_out, output_limit.amount.clone())
+                    .with_esdt_transfer(input_payment)
+                    .execute_on_dest_context::<EsdtTokenPayment>();
+
+                ManagedVec::from_single_item(result_payment)
+            }
+        };
+
+        let output_amount = self.get_swap_output_amount(&result_payments, &output_limit.token);
+        require!(
+            output_amount >= output_limit.amount,
+            ERR_ASH_OUTPUT_BELOW_LIMIT
+        );
+
+        let unswapped_payment = EgldOrEsdtTokenPayment::new(
+            payment.token_identifier,
+            payment.token_nonce,
+            payment.amount - input_amount,
+        );
+
+        (result_payments, unswapped_payment)
+    }
+
+    /// Swaps the payment asynchronously using the given adapter and sends the result to the destination
+    /// The unswapped part of the payment is sent to the destination right away
+    /// If the swap fails, the input is sent unswapped to the destination (see swap_callback)
+    fn swap_tokens_async(
+        &self,
+        stream_id: u64,
+        adapter: &SwapAdapter<Self::Api>,
+        payment: EgldOrEsdtTokenPayment,
+        steps: ManagedVec<AggregatorStep<Self::Api>>,
+        limits: ManagedVec<TokenAmount<Self::Api>>,
+        destination: &ManagedAddress,
+    ) -> ! {
+        let input_payment = self.prepare_swap_input(&payment, &steps);
+
+        let unswapped_amount = &payment.amount - &input_payment.amount;
+        if unswapped_amount > 0 {
+            self.send().direct(
+                destination,
+                &payment.token_identifier,
+                payment.token_nonce,
+                &unswapped_amount,
+            );
+        }
+
+        let callback = self.callbacks().swap_callback(
+            stream_id,
+            destination.clone(),
+            input_payment.clone(),
+            payment.token_identifier.is_egld(),
+        );
+
+        match adapter.adapter_type {
+            SwapAdapterType::AshAggregator => self
+                .ash_aggregator_proxy(adapter.address.clone())
+                .aggregate(steps, MultiValueEncoded::from(limits))
+                .with_esdt_transfer(input_payment)
+                .async_call()
+                .with_callback(callback)
+                .call_and_exit(),
+            SwapAdapterType::XExchangeRouter => self
+                .xexchange_router_proxy(adapter.address.clone())
+                .multi_pair_swap(self.get_xexchange_swap_operations(&steps, &limits))
+                .with_esdt_transfer(input_payment)
+                .async_call()
+                .with_callback(callback)
+                .call_and_exit(),
+            SwapAdapterType::SinglePool => self
+                .pair_proxy(adapter.address.clone())
+                .swap_tokens_fixed_input(
+                    steps.get(0).token_out,
+                    limits.get(limits.len() - 1).amount,
+                )
+                .with_esdt_transfer(input_payment)
+                .async_call()
+                .with_callback(callback)
+                .call_and_exit(),
+        }
+    }
+
+    /// Returns the amount of the output token received from a swap
+    fn get_swap_output_amount(
+        &self,
+        result_payments: &ManagedVec<EsdtTokenPayment>,
+        output_token: &TokenIdentifier,
+    ) -> BigUint {
+        let mut output_amount = BigUint::zero();
+        for result_payment in result_payments {
+            if &result_payment.token_identifier == output_token {
+                output_amount += result_payment.amount;
+            }
+        }
+
+        output_amount
+    }
+
+    /// Sends the swapped tokens to the destination
+    /// If the swap failed, the input tokens that were sent back are delivered unswapped instead (unwrapped for EGLD streams)
+    #[callback]
+    fn swap_callback(
+        &self,
+        stream_id: u64,
+        destination: ManagedAddress,
+        input_payment: EsdtTokenPayment,
+        is_egld: bool,
+        #[call_result] result: ManagedAsyncCallResult<IgnoreValue>,
+    ) {
+        match result {
+            ManagedAsyncCallResult::Ok(_) => {
+                let result_payments = self.call_value().all_esdt_transfers().clone_value();
+                if !result_payments.is_empty() {
+                    self.send().direct_multi(&destination, &result_payments);
+                }
+            }
+            ManagedAsyncCallResult::Err(err) => {
+                if is_egld {
+                    let (_, egld_amount) = self
+                        .unwrap_egld_payments(ManagedVec::from_single_item(input_payment.clone()));
+                    self.send().direct_egld(&destination, &egld_amount);
+                } else {
+                    self.send().direct_esdt(
+                        &destination,
+                        &input_payment.token_identifier,
+                        input_payment.token_nonce,
+                        &input_payment.amount,
+                    );
+                }
+
+                self.swap_failed_event(
+                    stream_id,
+                    &destination,
+                    &input_payment.token_identifier,
+                    &input_payment.amount,
+                    &err.err_msg,
+                );
+            }
+        }
+    }
+
+    /// Unwraps the WEGLD payments using the wrap EGLD contract
+    /// Returns the remaining ESDT payments and the unwrapped EGLD amount
+    fn unwrap_egld_payments(
+        &self,
+        payments: ManagedVec<EsdtTokenPayment>,
+    ) -> (ManagedVec<EsdtTokenPayment>, BigUint) {
+        let wrap_egld_token = self.wrap_egld_token().get();
+
+        let mut esdt_payments = ManagedVec::new();
+        let mut egld_amount = BigUint::zero();
+        for payment in &payments {
+            if payment.token_identifier == wrap_egld_token {
+                egld_amount += payment.amount;
+            } else {
+                esdt_payments.push(payment);
+            }
+        }
+
+        if egld_amount > 0 {
+            let _: IgnoreValue = self
+                .wrap_egld_proxy(self.wrap_egld_sc().get())
+                .unwrap_egld()
+                .with_esdt_transfer(EsdtTokenPayment::new(
+                    wrap_egld_token,
+                    0,
+                    egld_amount.clone(),
+                ))
+                .execute_on_dest_context();
+        }
+
+        (esdt_payments, egld_amount)
+    }
+}
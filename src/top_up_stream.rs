@@ -0,0 +1,84 @@
+use crate::{
+    create_stream::MAX_SEGMENTS,
+    errors::{
+        ERR_CANT_TOP_UP, ERR_INVALID_TOP_UP_TOKEN, ERR_TOO_MANY_SEGMENTS, ERR_TOP_UP_ONLY_SENDER,
+        ERR_ZERO_DEPOSIT,
+    },
+    storage::{BrokerFee, Segment},
+};
+
+multiversx_sc::imports!();
+
+#[multiversx_sc::module]
+pub trait TopUpStreamModule:
+    crate::storage::StorageModule
+    + crate::events::EventsModule
+    + crate::create_stream::CreateStreamModule
+    + crate::status::StatusModule
+    + crate::stream_nft::StreamNftModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+{
+    /// This endpoint can be used by the sender of a stream to add more tokens to a running stream
+    /// The new segments are appended after the current end time and must match the added amount (fees excluded)
+    /// The stream NFT attributes are refreshed right away if the NFT is in the custody of the contract,
+    /// otherwise the next time the NFT is sent to the contract (e.g. on claim)
+    #[payable("*")]
+    #[endpoint(topUpStream)]
+    fn top_up_stream(
+        &self,
+        stream_id: u64,
+        segments: ManagedVec<Segment<Self::Api>>,
+        brokers_opt: OptionalValue<ManagedVec<BrokerFee<Self::Api>>>,
+    ) {
+        let mut stream = self.get_stream(stream_id);
+
+        let caller = self.blockchain().get_caller();
+        require!(caller == stream.sender, ERR_TOP_UP_ONLY_SENDER);
+
+        let is_warm = self.is_warm(stream_id);
+        require!(is_warm, ERR_CANT_TOP_UP);
+
+        let (token_identifier, token_nonce, token_amount) =
+            self.call_value().egld_or_single_esdt().into_tuple();
+        require!(
+            token_identifier == stream.payment_token && token_nonce == stream.payment_nonce,
+            ERR_INVALID_TOP_UP_TOKEN
+        );
+        require!(token_amount > 0, ERR_ZERO_DEPOSIT);
+        require!(
+            stream.segments.len() + segments.len() <= MAX_SEGMENTS,
+            ERR_TOO_MANY_SEGMENTS
+        );
+
+        let top_up_amount = self.apply_fees(
+            stream_id,
+            &token_identifier,
+            token_nonce,
+            &token_amount,
+            &brokers_opt.into_option().unwrap_or_default(),
+        );
+        let top_up_duration = self.validate_stream_segments(&top_up_amount, &segments);
+
+        stream.deposit += &top_up_amount;
+        stream.end_time += top_up_duration;
+        for segment in &segments {
+            stream.segments.push(segment);
+        }
+
+        self.save_stream(stream_id, &stream);
+
+        if !self.stream_holder(stream_id).is_empty() {
+            let nft_attributes = self.get_stream_nft_attributes(&stream);
+            self.stream_nft_token()
+                .nft_update_attributes(stream.nft_nonce, &nft_attributes);
+        }
+
+        self.top_up_stream_event(
+            stream_id,
+            &top_up_amount,
+            &token_amount,
+            stream.end_time,
+            &segments,
+        );
+    }
+}
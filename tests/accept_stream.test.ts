@@ -0,0 +1,165 @@
+import { expect, test } from "vitest";
+import { d, e } from "xsuite";
+
+import {
+  ERR_ACCEPTANCE_DEADLINE_NOT_PASSED,
+  ERR_ACCEPTANCE_DEADLINE_PASSED,
+  ERR_CANT_CLAIM,
+  ERR_INVALID_ACCEPTANCE_DEADLINE,
+  ERR_RECLAIM_ONLY_SENDER,
+} from "./errors";
+import {
+  claimFromStream,
+  generateStreamSegment,
+  getStream,
+  requireEgldBalance,
+  requireStreamInvalid,
+  requireValidStreamNft,
+} from "./utils";
+
+const AWAITING_ACCEPTANCE_STATUS = 5;
+const PENDING_STATUS = 0;
+
+const createStreamWithAcceptance = (ctx, deadline: number) => {
+  return ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStream",
+    funcArgs: [
+      ctx.recipient_wallet,
+      e.U64(10),
+      generateStreamSegment(10, 1, 100),
+      e.U64(0),
+      e.Bool(true),
+      e.List(),
+      e.U(0),
+      e.U64(deadline),
+    ],
+    value: 10,
+  });
+};
+
+const acceptStream = (ctx, streamId: number) => {
+  return ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "acceptStream",
+    funcArgs: [e.U64(streamId)],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.stream_nft_token_identifier,
+        nonce: streamId,
+        amount: 1,
+      },
+    ],
+  });
+};
+
+const reclaimStream = (ctx, streamId: number) => {
+  return ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "reclaimStream",
+    funcArgs: [e.U64(streamId)],
+    value: 0,
+  });
+};
+
+const getStatusOf = async (ctx, streamId: number) => {
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getStatusOf",
+    funcArgs: [e.U64(streamId)],
+  });
+
+  return Number(d.U8().topDecode(returnData[0]));
+};
+
+test("Accept stream before deadline", async (ctx) => {
+  await createStreamWithAcceptance(ctx, 50);
+
+  expect((await getStream(ctx, 1)).acceptance_deadline).toBe(50n);
+  expect(await getStatusOf(ctx, 1)).toBe(AWAITING_ACCEPTANCE_STATUS);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 40,
+  });
+  await acceptStream(ctx, 1);
+
+  await requireValidStreamNft(ctx);
+  expect((await getStream(ctx, 1)).acceptance_deadline).toBe(null);
+  expect(await getStatusOf(ctx, 1)).not.toBe(AWAITING_ACCEPTANCE_STATUS);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 200,
+  });
+  await claimFromStream(ctx, 1);
+
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 10);
+});
+
+test("Can't claim a stream awaiting acceptance", async (ctx) => {
+  await createStreamWithAcceptance(ctx, 500);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 200,
+  });
+
+  await claimFromStream(ctx, 1).assertFail({ message: ERR_CANT_CLAIM });
+});
+
+test("Accept stream after deadline", async (ctx) => {
+  await createStreamWithAcceptance(ctx, 50);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 51,
+  });
+
+  await acceptStream(ctx, 1).assertFail({ message: ERR_ACCEPTANCE_DEADLINE_PASSED });
+});
+
+test("Reclaim stream after deadline", async (ctx) => {
+  await createStreamWithAcceptance(ctx, 50);
+
+  await reclaimStream(ctx, 1).assertFail({ message: ERR_ACCEPTANCE_DEADLINE_NOT_PASSED });
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 51,
+  });
+
+  await ctx.recipient_wallet
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 50_000_000,
+      funcName: "reclaimStream",
+      funcArgs: [e.U64(1)],
+      value: 0,
+    })
+    .assertFail({ message: ERR_RECLAIM_ONLY_SENDER });
+
+  await reclaimStream(ctx, 1);
+
+  await requireEgldBalance(ctx, ctx.sender_wallet, 1000);
+  await requireStreamInvalid(ctx, 1);
+});
+
+test("Stream without acceptance deadline", async (ctx) => {
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStream",
+    funcArgs: [ctx.recipient_wallet, e.U64(10), generateStreamSegment(10, 1, 100)],
+    value: 10,
+  });
+
+  expect(await getStatusOf(ctx, 1)).toBe(PENDING_STATUS);
+});
+
+test("Acceptance deadline in the past", async (ctx) => {
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 5,
+  });
+
+  await createStreamWithAcceptance(ctx, 5).assertFail({ message: ERR_INVALID_ACCEPTANCE_DEADLINE });
+});
@@ -1,7 +1,12 @@
 import { expect, test } from "vitest";
-import { e } from "xsuite";
+import { d, e } from "xsuite";
 
-import { ERR_BROKER_FEE_TOO_BIG } from "./errors";
+import {
+  ERR_BROKER_DISABLED,
+  ERR_BROKER_FEE_ABOVE_CAP,
+  ERR_BROKER_FEE_TOO_BIG,
+  ERR_BROKER_NOT_REGISTERED,
+} from "./errors";
 import { generateStreamSegment, getStream, requireEgldBalance } from "./utils";
 
 test("Stream created with broker fee", async (ctx) => {
@@ -45,3 +50,200 @@ test("Stream created with invalid broker fee", async (ctx) => {
     })
     .assertFail({ message: ERR_BROKER_FEE_TOO_BIG });
 });
+
+const createStreamWithBroker = (ctx, brokerAddress, fee: number) => {
+  return ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [
+      ctx.recipient_wallet,
+      e.List(generateStreamSegment(95, 1, 100)),
+      e.U64(0),
+      e.Bool(true),
+      e.Tuple(e.Addr(brokerAddress), e.U(fee)),
+    ],
+    value: 100,
+  });
+};
+
+const setBroker = (ctx, maxFee: number, defaultFee?: number) => {
+  const funcArgs = [ctx.deployer, e.U(maxFee)];
+  if (defaultFee !== undefined) {
+    funcArgs.push(e.U(defaultFee));
+  }
+
+  return ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setBroker",
+    funcArgs,
+    value: 0,
+  });
+};
+
+test("Stream created with registered broker default fee", async (ctx) => {
+  await setBroker(ctx, 5_00, 5_00);
+
+  await createStreamWithBroker(ctx, ctx.deployer.toTopBytes(), 0);
+
+  await requireEgldBalance(ctx, ctx.deployer, 5);
+
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getBrokerFeeVolume",
+    funcArgs: [ctx.deployer, e.Str("EGLD")],
+  });
+  expect(d.U().topDecode(returnData[0])).toBe(5n);
+});
+
+test("Stream created with fee above broker cap", async (ctx) => {
+  await setBroker(ctx, 2_00);
+
+  await createStreamWithBroker(ctx, ctx.deployer.toTopBytes(), 5_00).assertFail({
+    message: ERR_BROKER_FEE_ABOVE_CAP,
+  });
+});
+
+test("Stream created with disabled broker", async (ctx) => {
+  await setBroker(ctx, 5_00);
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setBrokerEnabled",
+    funcArgs: [ctx.deployer, e.Bool(false)],
+    value: 0,
+  });
+
+  await createStreamWithBroker(ctx, ctx.deployer.toTopBytes(), 5_00).assertFail({
+    message: ERR_BROKER_DISABLED,
+  });
+});
+
+test("Stream created with unregistered broker in registry mode", async (ctx) => {
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setBrokerRegistryEnabled",
+    funcArgs: [e.Bool(true)],
+    value: 0,
+  });
+
+  await createStreamWithBroker(ctx, ctx.deployer.toTopBytes(), 5_00).assertFail({
+    message: ERR_BROKER_NOT_REGISTERED,
+  });
+});
+
+test("Stream created with split broker fee", async (ctx) => {
+  const referrer = await ctx.world.createWallet();
+
+  const createStreamResult = await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [
+      ctx.recipient_wallet,
+      e.List(generateStreamSegment(92, 1, 100)),
+      e.U64(0),
+      e.Bool(true),
+      e.List(
+        e.Tuple(e.Addr(ctx.deployer.toTopBytes()), e.U(5_00)),
+        e.Tuple(e.Addr(referrer.toTopBytes()), e.U(3_00))
+      ),
+    ],
+    value: 100,
+  });
+
+  const stream = await getStream(ctx, parseInt(createStreamResult.returnData[0]));
+  expect(stream.deposit).toBe(92n);
+
+  await requireEgldBalance(ctx, ctx.deployer, 5);
+  await requireEgldBalance(ctx, referrer, 3);
+});
+
+test("Stream created with split broker fee above cap", async (ctx) => {
+  const referrer = await ctx.world.createWallet();
+
+  await ctx.sender_wallet
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 200_000_000,
+      funcName: "createStreamNow",
+      funcArgs: [
+        ctx.recipient_wallet,
+        e.List(generateStreamSegment(88, 1, 100)),
+        e.U64(0),
+        e.Bool(true),
+        e.List(
+          e.Tuple(e.Addr(ctx.deployer.toTopBytes()), e.U(6_00)),
+          e.Tuple(e.Addr(referrer.toTopBytes()), e.U(6_00))
+        ),
+      ],
+      value: 100,
+    })
+    .assertFail({ message: ERR_BROKER_FEE_TOO_BIG });
+});
+
+test("Stream created with unregistered split broker in registry mode", async (ctx) => {
+  const referrer = await ctx.world.createWallet();
+
+  await setBroker(ctx, 10_00);
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setBrokerRegistryEnabled",
+    funcArgs: [e.Bool(true)],
+    value: 0,
+  });
+
+  const createStreamWithSplitFee = () =>
+    ctx.sender_wallet.callContract({
+      callee: ctx.contract,
+      gasLimit: 200_000_000,
+      funcName: "createStreamNow",
+      funcArgs: [
+        ctx.recipient_wallet,
+        e.List(generateStreamSegment(92, 1, 100)),
+        e.U64(0),
+        e.Bool(true),
+        e.List(
+          e.Tuple(e.Addr(ctx.deployer.toTopBytes()), e.U(5_00)),
+          e.Tuple(e.Addr(referrer.toTopBytes()), e.U(3_00))
+        ),
+      ],
+      value: 100,
+    });
+
+  await createStreamWithSplitFee().assertFail({ message: ERR_BROKER_NOT_REGISTERED });
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setBroker",
+    funcArgs: [referrer, e.U(3_00)],
+    value: 0,
+  });
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setBrokerEnabled",
+    funcArgs: [referrer, e.Bool(false)],
+    value: 0,
+  });
+
+  await createStreamWithSplitFee().assertFail({ message: ERR_BROKER_DISABLED });
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setBrokerEnabled",
+    funcArgs: [referrer, e.Bool(true)],
+    value: 0,
+  });
+
+  await createStreamWithSplitFee();
+
+  await requireEgldBalance(ctx, ctx.deployer, 5);
+  await requireEgldBalance(ctx, referrer, 3);
+});
@@ -1,10 +1,12 @@
 import { expect, test } from "vitest";
 import { e } from "xsuite";
 
-import { ERR_INVALID_ROLE, ERR_INVALID_STREAM, ERR_ZERO_CLAIM } from "./errors";
+import { ERR_INVALID_ROLE, ERR_INVALID_STREAM, ERR_ZERO_CLAIM, ERR_ZERO_INVALID_CLAIM_AMOUNT } from "./errors";
 import {
   claimFromStream,
+  claimFromStreamAmount,
   createStream,
+  generateStreamNftAttr,
   generateStreamSegment,
   getStream,
   requireEgldBalance,
@@ -95,3 +97,42 @@ test("Successful claim multiple segments", async (ctx) => {
 
   await requireStreamInvalid(ctx, streamId);
 });
+
+test("Partial claim", async (ctx) => {
+  const streamId = await createStream(ctx, 600);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 300,
+  });
+
+  await claimFromStreamAmount(ctx, streamId, 6).assertFail({ message: ERR_ZERO_INVALID_CLAIM_AMOUNT });
+  await claimFromStreamAmount(ctx, streamId, 0).assertFail({ message: ERR_ZERO_INVALID_CLAIM_AMOUNT });
+
+  await claimFromStreamAmount(ctx, streamId, 2);
+
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 2);
+  await requireValidStreamNft(ctx);
+  expect((await getStream(ctx, streamId)).claimed_amount).toBe(2n);
+});
+
+test("Partial claim after the stream ended", async (ctx) => {
+  const streamId = await createStream(ctx, 600);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 650,
+  });
+
+  await claimFromStreamAmount(ctx, streamId, 4);
+
+  // The stream is kept and the NFT is sent back until everything is claimed
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 4);
+  const stream = await getStream(ctx, streamId);
+  expect(stream.claimed_amount).toBe(4n);
+  await requireValidStreamNft(ctx, 1, 1, generateStreamNftAttr(stream));
+
+  await claimFromStreamAmount(ctx, streamId, 6);
+
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 10);
+  await requireValidStreamNft(ctx, 0);
+  await requireStreamInvalid(ctx, streamId);
+});
@@ -0,0 +1,222 @@
+import { expect, test } from "vitest";
+import { assertAccount, d, e } from "xsuite";
+
+import { ERR_CALL_ALREADY_WHITELISTED, ERR_CALL_INVALID_RETURN_TOKEN, ERR_CALL_NOT_WHITELISTED } from "./errors";
+import { generateStreamSegment, getStream, requireEsdtBalance, requireValidStreamNft } from "./utils";
+
+const RECEIPT_TOKEN = "RECEIPT-123456";
+
+const whitelistCallEndpoint = (
+  ctx,
+  endpoint: string,
+  wallet = ctx.deployer,
+  target = ctx.deployer,
+  returnTokens: string[] = []
+) => {
+  return wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "whitelistCallEndpoint",
+    funcArgs: [target, e.Str(endpoint), ...returnTokens.map((token) => e.Str(token))],
+    value: 0,
+  });
+};
+
+const getWhitelistedCallEndpoints = async (ctx) => {
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getWhitelistedCallEndpoints",
+    funcArgs: [ctx.deployer],
+  });
+
+  return returnData.map((endpoint) => d.Str().topDecode(endpoint));
+};
+
+const claimFromStreamAndCall = (ctx, endpoint: string, target = ctx.deployer, args = [e.Str("arg")]) => {
+  return ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "claimFromStreamAndCall",
+    funcArgs: [e.U64(1), target, e.Str(endpoint), ...args],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.stream_nft_token_identifier,
+        nonce: 1,
+        amount: 1,
+      },
+    ],
+  });
+};
+
+test("Whitelist call endpoint", async (ctx) => {
+  await whitelistCallEndpoint(ctx, "stake");
+  await whitelistCallEndpoint(ctx, "deposit");
+
+  expect((await getWhitelistedCallEndpoints(ctx)).sort()).toEqual(["deposit", "stake"]);
+
+  await whitelistCallEndpoint(ctx, "stake").assertFail({ message: ERR_CALL_ALREADY_WHITELISTED });
+});
+
+test("Whitelist call endpoint not owner", async (ctx) => {
+  await whitelistCallEndpoint(ctx, "stake", ctx.sender_wallet).assertFail({
+    message: "Endpoint can only be called by owner",
+  });
+});
+
+test("Remove call endpoint", async (ctx) => {
+  await whitelistCallEndpoint(ctx, "stake");
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "removeCallEndpoint",
+    funcArgs: [ctx.deployer, e.Str("stake")],
+    value: 0,
+  });
+
+  expect(await getWhitelistedCallEndpoints(ctx)).toEqual([]);
+
+  await ctx.deployer
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 10_000_000,
+      funcName: "removeCallEndpoint",
+      funcArgs: [ctx.deployer, e.Str("stake")],
+      value: 0,
+    })
+    .assertFail({ message: ERR_CALL_NOT_WHITELISTED });
+});
+
+const createEsdtStream = (ctx) => {
+  return ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [ctx.recipient_wallet, generateStreamSegment(1000, 1, 100)],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.payment_esdt_token_identifier,
+        nonce: 0,
+        amount: 1000,
+      },
+    ],
+  });
+};
+
+const deployCallReceiverMock = (ctx) => {
+  return ctx.world.createContract({
+    code: "file:mocks/call-receiver/output/call-receiver-mock.wasm",
+    kvs: [
+      e.kvs.Mapper("receiptToken").Value(e.Str(RECEIPT_TOKEN)),
+      e.kvs.Esdts([{ id: RECEIPT_TOKEN, amount: 1000 }]),
+    ],
+  });
+};
+
+test("Claim and call", async (ctx) => {
+  const receiver = await deployCallReceiverMock(ctx);
+  await whitelistCallEndpoint(ctx, "deposit", ctx.deployer, receiver, [RECEIPT_TOKEN]);
+  await createEsdtStream(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  await claimFromStreamAndCall(ctx, "deposit", receiver, [e.U(100)]);
+
+  // The refund and the receipt tokens sent back by the target are forwarded to the caller
+  assertAccount(await receiver.getAccountWithKvs(), {
+    hasKvs: [
+      e.kvs.Esdts([
+        { id: ctx.payment_esdt_token_identifier, amount: 400 },
+        { id: RECEIPT_TOKEN, amount: 600 },
+      ]),
+    ],
+  });
+  await requireEsdtBalance(ctx, ctx.recipient_wallet, 100);
+  assertAccount(await ctx.recipient_wallet.getAccountWithKvs(), {
+    hasKvs: [e.kvs.Esdts([{ id: RECEIPT_TOKEN, amount: 400 }])],
+  });
+  await requireValidStreamNft(ctx, 1, 1);
+
+  const stream = await getStream(ctx, 1);
+  expect(stream.claimed_amount).toBe(500n);
+
+  // The rest of the deposit is still held by the contract
+  assertAccount(await ctx.contract.getAccountWithKvs(), {
+    hasKvs: [e.kvs.Esdts([{ id: ctx.payment_esdt_token_identifier, amount: 500 }])],
+  });
+});
+
+test("Claim and call an endpoint that is not whitelisted", async (ctx) => {
+  await whitelistCallEndpoint(ctx, "stake");
+  await createEsdtStream(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  await claimFromStreamAndCall(ctx, "deposit").assertFail({ message: ERR_CALL_NOT_WHITELISTED });
+
+  const stream = await getStream(ctx, 1);
+  expect(stream.claimed_amount).toBe(0n);
+});
+
+test("Claim and call with a return token that is not whitelisted", async (ctx) => {
+  const receiver = await deployCallReceiverMock(ctx);
+  await whitelistCallEndpoint(ctx, "deposit", ctx.deployer, receiver);
+  await createEsdtStream(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  await claimFromStreamAndCall(ctx, "deposit", receiver, [e.U(100)]).assertFail({
+    message: ERR_CALL_INVALID_RETURN_TOKEN,
+  });
+
+  // The return tokens of a whitelisted endpoint can be replaced
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setCallReturnTokens",
+    funcArgs: [receiver, e.Str("deposit"), e.Str(RECEIPT_TOKEN)],
+    value: 0,
+  });
+
+  await claimFromStreamAndCall(ctx, "deposit", receiver, [e.U(100)]);
+
+  await requireEsdtBalance(ctx, ctx.recipient_wallet, 100);
+  assertAccount(await ctx.recipient_wallet.getAccountWithKvs(), {
+    hasKvs: [e.kvs.Esdts([{ id: RECEIPT_TOKEN, amount: 400 }])],
+  });
+});
+
+test("Claim and call a target that deposits into the contract", async (ctx) => {
+  const receiver = await deployCallReceiverMock(ctx);
+  await whitelistCallEndpoint(ctx, "forward", ctx.deployer, receiver);
+  await createEsdtStream(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  // The target creates a new stream with the claimed tokens during the call
+  await claimFromStreamAndCall(ctx, "forward", receiver, [
+    ctx.contract,
+    e.Str("createStreamNow"),
+    ctx.sender_wallet,
+    generateStreamSegment(500, 1, 100),
+  ]);
+
+  // The deposit of the new stream is kept by the contract and nothing is forwarded to the caller
+  await requireEsdtBalance(ctx, ctx.recipient_wallet, 0);
+  assertAccount(await ctx.contract.getAccountWithKvs(), {
+    hasKvs: [e.kvs.Esdts([{ id: ctx.payment_esdt_token_identifier, amount: 1000 }])],
+  });
+
+  const stream = await getStream(ctx, 2);
+  expect(stream.deposit).toBe(500n);
+});
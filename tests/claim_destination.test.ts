@@ -0,0 +1,106 @@
+import { test } from "vitest";
+import { e } from "xsuite";
+
+import { ERR_CLAIM_DESTINATION_SC, ERR_INVALID_ROLE } from "./errors";
+import { claimFromStream, createStream, requireEgldBalance, requireValidStreamNft } from "./utils";
+
+const sendStreamNft = (ctx, funcName: string, funcArgs: any[], streamId = 1) => {
+  return ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName,
+    funcArgs: [e.U64(streamId), ...funcArgs],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.stream_nft_token_identifier,
+        nonce: streamId,
+        amount: 1,
+      },
+    ],
+  });
+};
+
+test("Claim to a different destination", async (ctx) => {
+  const destination = await ctx.world.createWallet();
+  await createStream(ctx, 600);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 300,
+  });
+
+  await sendStreamNft(ctx, "claimFromStreamTo", [destination]);
+
+  await requireEgldBalance(ctx, destination, 5);
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 0);
+  await requireValidStreamNft(ctx);
+});
+
+test("Claim to the contract", async (ctx) => {
+  await createStream(ctx, 600);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 300,
+  });
+
+  await sendStreamNft(ctx, "claimFromStreamTo", [ctx.contract]).assertFail({ message: ERR_CLAIM_DESTINATION_SC });
+});
+
+test("Claim to the persistent claim destination", async (ctx) => {
+  const destination = await ctx.world.createWallet();
+  await createStream(ctx, 600);
+
+  await sendStreamNft(ctx, "setClaimDestination", [destination]);
+  await requireValidStreamNft(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 300,
+  });
+
+  await claimFromStream(ctx, 1);
+
+  await requireEgldBalance(ctx, destination, 5);
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 0);
+
+  // Removing the claim destination sends the claimed tokens to the recipient again
+  await sendStreamNft(ctx, "setClaimDestination", []);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 450,
+  });
+
+  await claimFromStream(ctx, 1);
+
+  await requireEgldBalance(ctx, destination, 5);
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 2);
+});
+
+test("Claim after cancel to the persistent claim destination", async (ctx) => {
+  const destination = await ctx.world.createWallet();
+  await createStream(ctx, 600);
+
+  await sendStreamNft(ctx, "setClaimDestination", [destination]);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 300,
+  });
+
+  await sendStreamNft(ctx, "cancelStream", []);
+
+  await requireEgldBalance(ctx, destination, 5);
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 0);
+});
+
+test("Set claim destination without the stream NFT", async (ctx) => {
+  await createStream(ctx, 600);
+
+  await ctx.sender_wallet
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 50_000_000,
+      funcName: "setClaimDestination",
+      funcArgs: [e.U64(1), ctx.sender_wallet],
+      value: 0,
+    })
+    .assertFail({ message: ERR_INVALID_ROLE });
+});
@@ -0,0 +1,138 @@
+import { test } from "vitest";
+import { e } from "xsuite";
+
+import { ERR_NO_STREAM_NFTS } from "./errors";
+import {
+  createStream,
+  generateStreamSegment,
+  requireEgldBalance,
+  requireEsdtBalance,
+  requireStreamInvalid,
+  requireValidStreamNft,
+} from "./utils";
+
+const claimFromStreams = (ctx, nonces: number[]) => {
+  return ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 100_000_000,
+    funcName: "claimFromStreams",
+    funcArgs: [],
+    value: 0,
+    esdts: nonces.map((nonce) => ({
+      id: ctx.stream_nft_token_identifier,
+      nonce,
+      amount: 1,
+    })),
+  });
+};
+
+const createEsdtStream = (ctx, amount: number) => {
+  return ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [ctx.recipient_wallet, generateStreamSegment(amount, 1, 100)],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.payment_esdt_token_identifier,
+        nonce: 0,
+        amount,
+      },
+    ],
+  });
+};
+
+test("Claim from multiple streams", async (ctx) => {
+  await createStream(ctx, 600);
+  await createStream(ctx, 1200);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 650,
+  });
+
+  await claimFromStreams(ctx, [1, 2]);
+
+  // The first stream is finished so its NFT is burned, the second one is sent back
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 15);
+  await requireStreamInvalid(ctx, 1);
+  await requireValidStreamNft(ctx, 0, 1);
+  await requireValidStreamNft(ctx, 1, 2);
+});
+
+test("Claim from multiple streams grouped by token", async (ctx) => {
+  await createEsdtStream(ctx, 1000);
+  await createEsdtStream(ctx, 2000);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  await claimFromStreams(ctx, [1, 2]);
+
+  await requireEsdtBalance(ctx, ctx.recipient_wallet, 1500);
+  await requireValidStreamNft(ctx, 1, 1);
+  await requireValidStreamNft(ctx, 1, 2);
+});
+
+test("Claim from multiple streams skips streams with nothing to claim", async (ctx) => {
+  await createStream(ctx, 600);
+  await createStream(ctx, 600, 400);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 300,
+  });
+
+  await claimFromStreams(ctx, [1, 2]);
+
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 5);
+  await requireValidStreamNft(ctx, 1, 1);
+  await requireValidStreamNft(ctx, 1, 2);
+});
+
+test("Claim from multiple streams skips canceled streams", async (ctx) => {
+  await createStream(ctx, 600);
+  await createStream(ctx, 600);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 300,
+  });
+
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "cancelStream",
+    funcArgs: [e.U64(2)],
+    value: 0,
+  });
+
+  await claimFromStreams(ctx, [1, 2]);
+
+  // Only the first stream is claimed, the NFT of the canceled stream is sent back
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 5);
+  await requireValidStreamNft(ctx, 1, 1);
+  await requireValidStreamNft(ctx, 1, 2);
+
+  await ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "claimFromStreamAfterCancel",
+    funcArgs: [e.U64(2)],
+    value: 0,
+    esdts: [{ id: ctx.stream_nft_token_identifier, nonce: 2, amount: 1 }],
+  });
+
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 10);
+});
+
+test("Claim from multiple streams without NFTs", async (ctx) => {
+  await ctx.recipient_wallet
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 100_000_000,
+      funcName: "claimFromStreams",
+      funcArgs: [],
+      value: 0,
+    })
+    .assertFail({ message: ERR_NO_STREAM_NFTS });
+});
@@ -0,0 +1,119 @@
+import { test } from "vitest";
+import { assertAccount, e } from "xsuite";
+
+import {
+  ERR_CLAIM_OPERATOR_ALREADY_ADDED,
+  ERR_CLAIM_OPERATOR_NOT_FOUND,
+  ERR_INVALID_CLAIM_OPERATOR,
+  ERR_ONLY_STREAM_HOLDER,
+  ERR_STREAM_NFT_NOT_IN_CUSTODY,
+} from "./errors";
+import { createStream, requireEgldBalance, requireStreamInvalid, requireValidStreamNft } from "./utils";
+
+const depositStreamNft = (ctx, streamId = 1) => {
+  return ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "depositStreamNft",
+    funcArgs: [e.U64(streamId)],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.stream_nft_token_identifier,
+        nonce: streamId,
+        amount: 1,
+      },
+    ],
+  });
+};
+
+const callStreamEndpoint = (wallet, ctx, funcName: string, funcArgs: any[] = [], streamId = 1) => {
+  return wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName,
+    funcArgs: [e.U64(streamId), ...funcArgs],
+    value: 0,
+  });
+};
+
+const requireStreamNftInCustody = async (ctx, amount = 1, nonce = 1) => {
+  assertAccount(await ctx.contract.getAccountWithKvs(), {
+    hasKvs: [e.kvs.Esdts([{ id: ctx.stream_nft_token_identifier, nonce, amount }])],
+  });
+};
+
+test("Claim by operator", async (ctx) => {
+  const operator = await ctx.world.createWallet();
+  await createStream(ctx, 600);
+
+  await depositStreamNft(ctx);
+  await requireStreamNftInCustody(ctx);
+
+  await callStreamEndpoint(ctx.recipient_wallet, ctx, "addClaimOperator", [operator]);
+  await callStreamEndpoint(ctx.recipient_wallet, ctx, "addClaimOperator", [operator]).assertFail({
+    message: ERR_CLAIM_OPERATOR_ALREADY_ADDED,
+  });
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 300,
+  });
+
+  await callStreamEndpoint(operator, ctx, "claimFromStreamByOperator");
+
+  // The claimed tokens are sent to the holder and the NFT stays in custody
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 5);
+  await requireEgldBalance(ctx, operator, 0);
+  await requireStreamNftInCustody(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 650,
+  });
+
+  await callStreamEndpoint(operator, ctx, "claimFromStreamByOperator");
+
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 10);
+  await requireStreamNftInCustody(ctx, 0);
+  await requireStreamInvalid(ctx, 1);
+});
+
+test("Revoke claim operator", async (ctx) => {
+  const operator = await ctx.world.createWallet();
+  await createStream(ctx, 600);
+
+  await depositStreamNft(ctx);
+  await callStreamEndpoint(ctx.recipient_wallet, ctx, "addClaimOperator", [operator]);
+  await callStreamEndpoint(ctx.recipient_wallet, ctx, "revokeClaimOperator", [operator]);
+  await callStreamEndpoint(ctx.recipient_wallet, ctx, "revokeClaimOperator", [operator]).assertFail({
+    message: ERR_CLAIM_OPERATOR_NOT_FOUND,
+  });
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 300,
+  });
+
+  await callStreamEndpoint(operator, ctx, "claimFromStreamByOperator").assertFail({
+    message: ERR_INVALID_CLAIM_OPERATOR,
+  });
+});
+
+test("Withdraw stream NFT from custody", async (ctx) => {
+  const operator = await ctx.world.createWallet();
+  await createStream(ctx, 600);
+
+  await depositStreamNft(ctx);
+  await callStreamEndpoint(ctx.recipient_wallet, ctx, "addClaimOperator", [operator]);
+
+  await callStreamEndpoint(operator, ctx, "withdrawStreamNft").assertFail({ message: ERR_ONLY_STREAM_HOLDER });
+  await callStreamEndpoint(ctx.recipient_wallet, ctx, "withdrawStreamNft");
+
+  await requireValidStreamNft(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 300,
+  });
+
+  await callStreamEndpoint(operator, ctx, "claimFromStreamByOperator").assertFail({
+    message: ERR_STREAM_NFT_NOT_IN_CUSTODY,
+  });
+});
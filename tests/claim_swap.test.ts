@@ -0,0 +1,332 @@
+import { expect, test } from "vitest";
+import { assertAccount, e } from "xsuite";
+
+import {
+  ERR_ASH_INVALID_INPUT_AMOUNT,
+  ERR_ASH_INVALID_INPUT_TOKEN,
+  ERR_ASH_OUTPUT_BELOW_LIMIT,
+  ERR_ASYNC_SWAP_OUTPUT_LIMIT,
+  ERR_CANT_CLAIM,
+  ERR_ZERO_INVALID_CLAIM_AMOUNT,
+} from "./errors";
+import {
+  cancelStream,
+  claimFromStream,
+  deployAshAggregatorMock,
+  deployWrapEgldMock,
+  generateStreamSegment,
+  getStream,
+  requireEgldBalance,
+  requireEsdtBalance,
+  requireStreamInvalid,
+  requireValidStreamNft,
+} from "./utils";
+
+const OUTPUT_TOKEN = "USDC-c76f1f";
+const WEGLD_TOKEN = "WEGLD-bd4d79";
+
+const generateSwapStep = (ctx, tokenIn: string, amountIn: number, tokenOut = OUTPUT_TOKEN) => {
+  return e.Tuple(
+    e.Str(tokenIn),
+    e.Str(tokenOut),
+    e.U(amountIn),
+    e.Addr(ctx.deployer.toTopBytes()),
+    e.Str("swap"),
+    e.List()
+  );
+};
+
+const generateTokenAmount = (token: string, amount: number) => {
+  return e.Tuple(e.Str(token), e.U(amount));
+};
+
+const createEsdtStream = (ctx) => {
+  return ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [ctx.recipient_wallet, generateStreamSegment(1000, 1, 100)],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.payment_esdt_token_identifier,
+        nonce: 0,
+        amount: 1000,
+      },
+    ],
+  });
+};
+
+const claimFromStreamSwap = (
+  ctx,
+  tokenIn: string,
+  amountIn: number,
+  amount?: number,
+  unwrapEgld?: boolean,
+  tokenOut = OUTPUT_TOKEN
+) => {
+  const funcArgs = [
+    e.U64(1),
+    e.List(generateSwapStep(ctx, tokenIn, amountIn, tokenOut)),
+    e.List(generateTokenAmount(tokenIn, amountIn), generateTokenAmount(tokenOut, 1)),
+  ];
+  if (amount !== undefined) {
+    funcArgs.push(e.U(amount));
+  }
+  if (unwrapEgld !== undefined) {
+    funcArgs.push(e.Bool(unwrapEgld));
+  }
+
+  return ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 100_000_000,
+    funcName: "claimFromStreamSwap",
+    funcArgs,
+    value: 0,
+    esdts: [
+      {
+        id: ctx.stream_nft_token_identifier,
+        nonce: 1,
+        amount: 1,
+      },
+    ],
+  });
+};
+
+const claimFromStreamSwapAsync = (ctx, amountIn: number, amount: number, amountOutMin = 0) => {
+  return ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 100_000_000,
+    funcName: "claimFromStreamSwapAsync",
+    funcArgs: [
+      e.U64(1),
+      e.List(generateSwapStep(ctx, ctx.payment_esdt_token_identifier, amountIn)),
+      e.List(
+        generateTokenAmount(ctx.payment_esdt_token_identifier, amountIn),
+        generateTokenAmount(OUTPUT_TOKEN, amountOutMin)
+      ),
+      e.U(amount),
+    ],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.stream_nft_token_identifier,
+        nonce: 1,
+        amount: 1,
+      },
+    ],
+  });
+};
+
+const requireOutputBalance = async (ctx, amount: number) => {
+  assertAccount(await ctx.recipient_wallet.getAccountWithKvs(), {
+    hasKvs: [e.kvs.Esdts([{ id: OUTPUT_TOKEN, amount }])],
+  });
+};
+
+test("Claim and swap part of a settled stream", async (ctx) => {
+  await createEsdtStream(ctx);
+  await deployAshAggregatorMock(ctx, OUTPUT_TOKEN, 800);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 200,
+  });
+
+  await claimFromStreamSwap(ctx, ctx.payment_esdt_token_identifier, 400);
+
+  // Only the swap input is claimed, so the stream and its NFT are kept for the rest of the deposit
+  await requireOutputBalance(ctx, 800);
+  await requireValidStreamNft(ctx, 1, 1);
+  const stream = await getStream(ctx, 1);
+  expect(stream.claimed_amount).toBe(400n);
+
+  await claimFromStream(ctx, 1);
+
+  await requireEsdtBalance(ctx, ctx.recipient_wallet, 600);
+  await requireStreamInvalid(ctx, 1);
+});
+
+test("Claim and swap refunds the unswapped input", async (ctx) => {
+  await createEsdtStream(ctx);
+  await deployAshAggregatorMock(ctx, OUTPUT_TOKEN, 800);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  await claimFromStreamSwap(ctx, ctx.payment_esdt_token_identifier, 400, 500);
+
+  await requireOutputBalance(ctx, 800);
+  await requireEsdtBalance(ctx, ctx.recipient_wallet, 100);
+  await requireValidStreamNft(ctx, 1, 1);
+
+  const stream = await getStream(ctx, 1);
+  expect(stream.claimed_amount).toBe(500n);
+});
+
+test("Claim and swap with an output below the limit", async (ctx) => {
+  await createEsdtStream(ctx);
+  await deployAshAggregatorMock(ctx, OUTPUT_TOKEN, 0);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 200,
+  });
+
+  await claimFromStreamSwap(ctx, ctx.payment_esdt_token_identifier, 400).assertFail({
+    message: ERR_ASH_OUTPUT_BELOW_LIMIT,
+  });
+
+  const stream = await getStream(ctx, 1);
+  expect(stream.claimed_amount).toBe(0n);
+});
+
+test("Claim and swap more than the settled stream balance", async (ctx) => {
+  await createEsdtStream(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 200,
+  });
+
+  await claimFromStreamSwap(ctx, ctx.payment_esdt_token_identifier, 1001).assertFail({
+    message: ERR_ZERO_INVALID_CLAIM_AMOUNT,
+  });
+
+  const stream = await getStream(ctx, 1);
+  expect(stream.claimed_amount).toBe(0n);
+});
+
+test("Claim and swap with an invalid input token", async (ctx) => {
+  await createEsdtStream(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 200,
+  });
+
+  await claimFromStreamSwap(ctx, WEGLD_TOKEN, 500, 500).assertFail({
+    message: ERR_ASH_INVALID_INPUT_TOKEN,
+  });
+});
+
+test("Claim and swap with a route bigger than the claimed amount", async (ctx) => {
+  await createEsdtStream(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 200,
+  });
+
+  await claimFromStreamSwap(ctx, ctx.payment_esdt_token_identifier, 500, 400).assertFail({
+    message: ERR_ASH_INVALID_INPUT_AMOUNT,
+  });
+
+  // The stream is left untouched, so the whole deposit can still be claimed
+  const stream = await getStream(ctx, 1);
+  expect(stream.claimed_amount).toBe(0n);
+  expect(stream.deposit).toBe(1000n);
+});
+
+test("Claim and swap from a cancelled stream", async (ctx) => {
+  await createEsdtStream(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  await cancelStream(ctx, 1, true, true);
+  await requireEsdtBalance(ctx, ctx.sender_wallet, 5_500);
+
+  await claimFromStreamSwap(ctx, ctx.payment_esdt_token_identifier, 500).assertFail({
+    message: ERR_CANT_CLAIM,
+  });
+
+  const stream = await getStream(ctx, 1);
+  expect(stream.balances_after_cancel.recipient_balance).toBe(500n);
+});
+
+test("Claim and swap to EGLD with an invalid input token", async (ctx) => {
+  await createEsdtStream(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 200,
+  });
+
+  await claimFromStreamSwap(ctx, WEGLD_TOKEN, 500, 500, true).assertFail({
+    message: ERR_ASH_INVALID_INPUT_TOKEN,
+  });
+
+  const stream = await getStream(ctx, 1);
+  expect(stream.claimed_amount).toBe(0n);
+});
+
+test("Claim and swap to EGLD", async (ctx) => {
+  await createEsdtStream(ctx);
+  await deployAshAggregatorMock(ctx, WEGLD_TOKEN, 300);
+  const wrapEgld = await deployWrapEgldMock(ctx, WEGLD_TOKEN, 300);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  await claimFromStreamSwap(ctx, ctx.payment_esdt_token_identifier, 400, undefined, true, WEGLD_TOKEN);
+
+  // The WEGLD output is unwrapped, so the recipient only receives EGLD
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 300);
+  assertAccount(await ctx.recipient_wallet.getAccountWithKvs(), {
+    hasKvs: [e.kvs.Esdts([{ id: WEGLD_TOKEN, amount: 0 }])],
+  });
+  assertAccount(await wrapEgld.getAccountWithKvs(), {
+    balance: 0,
+    hasKvs: [e.kvs.Esdts([{ id: WEGLD_TOKEN, amount: 600 }])],
+  });
+  await requireValidStreamNft(ctx, 1, 1);
+
+  const stream = await getStream(ctx, 1);
+  expect(stream.claimed_amount).toBe(400n);
+});
+
+test("Claim and swap async falls back to the claimed tokens when the swap fails", async (ctx) => {
+  await createEsdtStream(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  // The aggregator set in the setup is not deployed, so the swap fails
+  await claimFromStreamSwapAsync(ctx, 400, 500);
+
+  await requireEsdtBalance(ctx, ctx.recipient_wallet, 500);
+  await requireValidStreamNft(ctx, 1, 1);
+
+  const stream = await getStream(ctx, 1);
+  expect(stream.claimed_amount).toBe(500n);
+});
+
+test("Claim and swap async", async (ctx) => {
+  await createEsdtStream(ctx);
+  await deployAshAggregatorMock(ctx, OUTPUT_TOKEN, 800);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  await claimFromStreamSwapAsync(ctx, 400, 500);
+
+  // The swapped tokens and the unswapped part of the claim are both delivered
+  await requireOutputBalance(ctx, 800);
+  await requireEsdtBalance(ctx, ctx.recipient_wallet, 100);
+  await requireValidStreamNft(ctx, 1, 1);
+
+  const stream = await getStream(ctx, 1);
+  expect(stream.claimed_amount).toBe(500n);
+});
+
+test("Claim and swap async with a minimum output", async (ctx) => {
+  await createEsdtStream(ctx);
+  await deployAshAggregatorMock(ctx, OUTPUT_TOKEN, 800);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  // The input can't be sent back after a successful swap, so a minimum output requires the sync swap
+  await claimFromStreamSwapAsync(ctx, 400, 500, 1).assertFail({ message: ERR_ASYNC_SWAP_OUTPUT_LIMIT });
+});
@@ -1,8 +1,15 @@
 import { expect, test } from "vitest";
 import { e } from "xsuite";
 
-import { ERR_CLIFF_TOO_BIG, ERR_ZERO_CLAIM } from "./errors";
-import { claimFromStream, createStream, generateStreamSegment, getStream, requireValidStreamNft } from "./utils";
+import { ERR_CLIFF_AMOUNT_TOO_BIG, ERR_CLIFF_TOO_BIG, ERR_ZERO_CLAIM } from "./errors";
+import {
+  claimFromStream,
+  createStream,
+  generateStreamSegment,
+  getRecipientBalance,
+  getStream,
+  requireValidStreamNft,
+} from "./utils";
 
 test("Valid cliff", async (ctx) => {
   const streamId = await createStream(ctx, 632, 200);
@@ -44,3 +51,56 @@ test("Cliff too big", async (ctx) => {
     })
     .assertFail({ message: ERR_CLIFF_TOO_BIG });
 });
+
+test("Cliff amount released at the cliff", async (ctx) => {
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [
+      ctx.recipient_wallet,
+      e.List(generateStreamSegment(0, 1, 100), generateStreamSegment(75, 1, 200)),
+      e.U64(100),
+      e.Bool(true),
+      e.List(),
+      e.U(25),
+    ],
+    value: 100,
+  });
+
+  const stream = await getStream(ctx, 1);
+  expect(stream.deposit).toBe(100n);
+  expect(stream.cliff_amount).toBe(25n);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+  expect(await getRecipientBalance(ctx, 1)).toBe(0n);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 100,
+  });
+  expect(await getRecipientBalance(ctx, 1)).toBe(25n);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 200,
+  });
+  expect(await getRecipientBalance(ctx, 1)).toBe(62n);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 301,
+  });
+  expect(await getRecipientBalance(ctx, 1)).toBe(100n);
+});
+
+test("Cliff amount too big", async (ctx) => {
+  await ctx.sender_wallet
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 200_000_000,
+      funcName: "createStreamNow",
+      funcArgs: [ctx.recipient_wallet, generateStreamSegment(1, 1, 632), e.U64(10), e.Bool(true), e.List(), e.U(2)],
+      value: 1,
+    })
+    .assertFail({ message: ERR_CLIFF_AMOUNT_TOO_BIG });
+});
@@ -2,6 +2,7 @@ import { expect, test } from "vitest";
 import { d, e } from "xsuite";
 
 import {
+  ERR_ASH_INVALID_STEPS_LENGTH,
   ERR_INVALID_SEGMENTS_DEPOSIT,
   ERR_INVALID_SEGMENTS_DURATION,
   ERR_START_TIME,
@@ -54,14 +55,17 @@ test("Create valid stream with ESDT", async (ctx) => {
     start_time: 0n,
     end_time: 632n,
     cliff: 12n,
+    cliff_amount: 0n,
     segments: [
       {
         amount: 3000n,
         exponent: 1n,
+        exponent_denominator: 1n,
         duration: 632n,
       },
     ],
     balances_after_cancel: null,
+    acceptance_deadline: null,
   });
 });
 
@@ -94,14 +98,17 @@ test("Create valid stream with EGLD", async (ctx) => {
     start_time: 0n,
     end_time: 632n,
     cliff: 12n,
+    cliff_amount: 0n,
     segments: [
       {
         amount: 3n,
         exponent: 1n,
+        exponent_denominator: 1n,
         duration: 632n,
       },
     ],
     balances_after_cancel: null,
+    acceptance_deadline: null,
   });
 });
 
@@ -134,14 +141,17 @@ test("Create valid stream with start & end time", async (ctx) => {
     start_time: 100n,
     end_time: 700n,
     cliff: 12n,
+    cliff_amount: 0n,
     segments: [
       {
         amount: 3n,
         exponent: 1n,
+        exponent_denominator: 1n,
         duration: 600n,
       },
     ],
     balances_after_cancel: null,
+    acceptance_deadline: null,
   });
 });
 
@@ -232,3 +242,39 @@ test("Create stream invalid segment deposit", async (ctx) => {
     })
     .assertFail({ message: ERR_INVALID_SEGMENTS_DEPOSIT });
 });
+
+test("Stream id counter", async (ctx) => {
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [ctx.recipient_wallet, generateStreamSegment(3, 1, 632)],
+    value: 3,
+  });
+
+  const { returnData: lastStreamId } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getLastStreamId",
+    funcArgs: [],
+  });
+  expect(d.U64().topDecode(lastStreamId[0])).toBe(1n);
+
+  const { returnData: streamId } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getStreamIdByNftNonce",
+    funcArgs: [e.U64(1)],
+  });
+  expect(d.U64().topDecode(streamId[0])).toBe(1n);
+});
+
+test("Stream with swap without steps", async (ctx) => {
+  await ctx.sender_wallet
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 200_000_000,
+      funcName: "createStreamSwap",
+      funcArgs: [ctx.recipient_wallet, e.U64(0), generateStreamSegment(1, 1, 632), e.List(), e.List()],
+      value: 1,
+    })
+    .assertFail({ message: ERR_ASH_INVALID_STEPS_LENGTH });
+});
@@ -0,0 +1,144 @@
+import { expect, test } from "vitest";
+import { d, e } from "xsuite";
+
+import { ERR_INSUFFICIENT_BATCH_PAYMENT, ERR_NO_STREAMS } from "./errors";
+import { generateStreamSegment, getStream, requireEgldBalance, requireValidStreamNft } from "./utils";
+
+const generateStreamRequest = (recipient: any, token: string, amount: number, segmentAmount: number) => {
+  return e.Tuple(
+    e.Addr(recipient.toTopBytes()),
+    e.Str(token),
+    e.U64(0),
+    e.U(amount),
+    e.U64(0),
+    e.List(generateStreamSegment(segmentAmount, 1, 100)),
+    e.U64(0),
+    e.U(0),
+    e.Bool(true)
+  );
+};
+
+test("Create multiple streams", async (ctx) => {
+  const secondRecipient = await ctx.world.createWallet();
+
+  const { returnData } = await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 300_000_000,
+    funcName: "createStreams",
+    funcArgs: [
+      e.List(
+        generateStreamRequest(ctx.recipient_wallet, "EGLD", 10, 10),
+        generateStreamRequest(secondRecipient, "EGLD", 20, 20)
+      ),
+    ],
+    value: 30,
+  });
+
+  expect(returnData.map((id) => d.U64().topDecode(id))).toEqual([1n, 2n]);
+
+  const firstStream = await getStream(ctx, 1);
+  expect(firstStream.deposit).toBe(10n);
+  const secondStream = await getStream(ctx, 2);
+  expect(secondStream.deposit).toBe(20n);
+
+  await requireValidStreamNft(ctx, 1, 1);
+});
+
+test("Create multiple streams with leftover refund", async (ctx) => {
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 300_000_000,
+    funcName: "createStreams",
+    funcArgs: [e.List(generateStreamRequest(ctx.recipient_wallet, "EGLD", 10, 10))],
+    value: 25,
+  });
+
+  await requireEgldBalance(ctx, ctx.sender_wallet, 990);
+});
+
+test("Create multiple streams with ESDT", async (ctx) => {
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 300_000_000,
+    funcName: "createStreams",
+    funcArgs: [
+      e.List(
+        generateStreamRequest(ctx.recipient_wallet, ctx.payment_esdt_token_identifier, 1000, 1000),
+        generateStreamRequest(ctx.recipient_wallet, ctx.payment_esdt_token_identifier, 2000, 2000)
+      ),
+    ],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.payment_esdt_token_identifier,
+        nonce: 0,
+        amount: 3000,
+      },
+    ],
+  });
+
+  const stream = await getStream(ctx, 2);
+  expect(stream.payment_token).toBe(ctx.payment_esdt_token_identifier);
+  expect(stream.deposit).toBe(2000n);
+});
+
+test("Create multiple streams with protocol fee", async (ctx) => {
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setProtocolFee",
+    funcArgs: [e.Str("EGLD"), e.U(10_00n)],
+    value: 0,
+  });
+
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 300_000_000,
+    funcName: "createStreams",
+    funcArgs: [
+      e.List(
+        generateStreamRequest(ctx.recipient_wallet, "EGLD", 10, 9),
+        generateStreamRequest(ctx.recipient_wallet, "EGLD", 20, 18)
+      ),
+    ],
+    value: 30,
+  });
+
+  expect((await getStream(ctx, 1)).deposit).toBe(9n);
+  expect((await getStream(ctx, 2)).deposit).toBe(18n);
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getAccruedProtocolFee",
+    funcArgs: [e.Str("EGLD"), e.U64(0)],
+  });
+  expect(d.U().topDecode(returnData[0])).toBe(3n);
+});
+
+test("Create multiple streams with insufficient payment", async (ctx) => {
+  await ctx.sender_wallet
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 300_000_000,
+      funcName: "createStreams",
+      funcArgs: [
+        e.List(
+          generateStreamRequest(ctx.recipient_wallet, "EGLD", 10, 10),
+          generateStreamRequest(ctx.recipient_wallet, "EGLD", 20, 20)
+        ),
+      ],
+      value: 20,
+    })
+    .assertFail({ message: ERR_INSUFFICIENT_BATCH_PAYMENT });
+});
+
+test("Create multiple streams with empty list", async (ctx) => {
+  await ctx.sender_wallet
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 300_000_000,
+      funcName: "createStreams",
+      funcArgs: [e.List()],
+      value: 20,
+    })
+    .assertFail({ message: ERR_NO_STREAMS });
+});
@@ -19,6 +19,58 @@ export const ERR_SEND_ONE_STREAM_NFT = "You can only send one stream NFT at a ti
 export const ERR_INVALID_NFT_TOKEN_NONCE = "Invalid token nonce";
 export const ERR_INVALID_ROLE = "Unauthorized! Invalid Role";
 export const ERR_SEGMENT_EXPONENT_DENOMINATOR_ZERO = "Segment exponent denominator must be greater than 0";
+export const ERR_SEGMENT_EXPONENT_TOO_BIG = "Segment exponent is too big";
+export const ERR_SEGMENT_EXPONENT_DENOMINATOR_TOO_BIG = "Segment exponent denominator is too big";
 export const ERR_INVALID_SEGMENTS_DURATION = "Invalid segments duration";
 export const ERR_INVALID_SEGMENTS_DEPOSIT = "Invalid segments deposit";
 export const ERR_TOO_MANY_SEGMENTS = "Too many segments";
+export const ERR_NO_STREAMS = "No streams to create";
+export const ERR_INSUFFICIENT_BATCH_PAYMENT = "Insufficient payment for the streams batch";
+export const ERR_TOP_UP_ONLY_SENDER = "Only sender can top up the stream";
+export const ERR_CANT_TOP_UP = "This stream can't be topped up";
+export const ERR_INVALID_TOP_UP_TOKEN = "Top up token doesn't match the stream token";
+export const ERR_CLIFF_AMOUNT_TOO_BIG = "Cliff amount can't be bigger than the deposit";
+export const ERR_SWAP_NO_STREAM_TOKEN = "Swap didn't return any stream token";
+export const ERR_INVALID_TREASURY_SHARES = "Treasury shares must add up to 100%";
+export const ERR_NO_PROTOCOL_FEES = "No protocol fees to withdraw";
+export const ERR_PROTOCOL_FEE_TOO_BIG = "Protocol fee can't be bigger than 10%";
+export const ERR_PROTOCOL_FEE_NOT_ANNOUNCED = "Protocol fee increase must be announced";
+export const ERR_NO_PENDING_PROTOCOL_FEE = "No pending protocol fee";
+export const ERR_PROTOCOL_FEE_DELAY = "Protocol fee delay has not passed";
+export const ERR_PROTOCOL_FEE_DELAY_DECREASE = "Protocol fee delay decrease must be announced";
+export const ERR_NO_PENDING_PROTOCOL_FEE_DELAY = "No pending protocol fee delay";
+export const ERR_PROTOCOL_FEE_DELAY_TOO_BIG = "Protocol fee delay is too big";
+export const ERR_BROKER_FEE_ABOVE_CAP = "Broker fee is bigger than the broker cap";
+export const ERR_BROKER_NOT_REGISTERED = "Broker is not registered";
+export const ERR_BROKER_DISABLED = "Broker is disabled";
+export const ERR_ASH_INVALID_STEPS_LENGTH = "Ash: Invalid steps length";
+export const ERR_INVALID_ACCEPTANCE_DEADLINE = "Acceptance deadline must be in the future";
+export const ERR_NOT_AWAITING_ACCEPTANCE = "Stream is not awaiting acceptance";
+export const ERR_ACCEPTANCE_DEADLINE_PASSED = "Acceptance deadline has passed";
+export const ERR_ACCEPTANCE_DEADLINE_NOT_PASSED = "Acceptance deadline has not passed";
+export const ERR_RECLAIM_ONLY_SENDER = "Only sender can reclaim the stream";
+export const ERR_STREAM_NOT_HASH_LOCKED = "Stream is not hash locked";
+export const ERR_INVALID_SECRET = "Invalid secret";
+export const ERR_REDEEM_NOT_COMMITTED = "Redeem was not committed in a previous block";
+export const ERR_STREAM_HASH_LOCKED = "Stream is still hash locked";
+export const ERR_NO_STREAM_NFTS = "No stream NFTs sent";
+export const ERR_CLAIM_DESTINATION_SC = "Claim destination can't be the contract";
+export const ERR_STREAM_NFT_NOT_IN_CUSTODY = "Stream NFT is not in custody";
+export const ERR_ONLY_STREAM_HOLDER = "Only the stream holder can do this";
+export const ERR_INVALID_CLAIM_OPERATOR = "Invalid claim operator";
+export const ERR_CLAIM_OPERATOR_ALREADY_ADDED = "Claim operator already added";
+export const ERR_CLAIM_OPERATOR_NOT_FOUND = "Claim operator not found";
+export const ERR_CANT_SETTLE = "Stream can't be settled";
+export const ERR_ZERO_INVALID_CLAIM_AMOUNT = "invalid claim amount";
+export const ERR_ASH_INVALID_INPUT_TOKEN = "Ash: Invalid input token";
+export const ERR_ASH_INVALID_INPUT_AMOUNT = "Ash: Invalid input amount";
+export const ERR_ASH_OUTPUT_BELOW_LIMIT = "Ash: Output amount below the limit";
+export const ERR_ASYNC_SWAP_OUTPUT_LIMIT = "Async swaps can't have a minimum output";
+export const ERR_SWAP_ADAPTER_NOT_FOUND = "Swap adapter not found";
+export const ERR_SWAP_ADAPTER_INVALID_STEPS = "Swap adapter doesn't support the swap steps";
+export const ERR_CALL_NOT_WHITELISTED = "Call target is not whitelisted";
+export const ERR_CALL_ALREADY_WHITELISTED = "Call target is already whitelisted";
+export const ERR_CALL_INVALID_RETURN_TOKEN = "Call target sent back a token that is not whitelisted";
+export const ERR_CANT_SPLIT = "Stream can't be split";
+export const ERR_INVALID_SPLIT_SHARES = "Invalid split shares";
+export const ERR_SPLIT_ABOVE_STREAMED = "Split stream would have more claimed than streamed";
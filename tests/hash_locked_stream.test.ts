@@ -0,0 +1,169 @@
+import { createHash } from "crypto";
+import { expect, test } from "vitest";
+import { assertAccount, d, e } from "xsuite";
+
+import {
+  ERR_ACCEPTANCE_DEADLINE_PASSED,
+  ERR_INVALID_SECRET,
+  ERR_REDEEM_NOT_COMMITTED,
+  ERR_STREAM_HASH_LOCKED,
+  ERR_STREAM_NOT_HASH_LOCKED,
+  ERR_STREAM_TO_CALLER,
+} from "./errors";
+import {
+  createStream,
+  generateStreamSegment,
+  getStream,
+  requireEgldBalance,
+  requireStreamInvalid,
+  requireValidStreamNft,
+} from "./utils";
+
+const SECRET = "coindrip-secret";
+const AWAITING_REDEEM_STATUS = 6;
+
+const createHashLockedStream = (ctx, expiry: number) => {
+  const secretHash = createHash("sha256").update(SECRET).digest("hex");
+
+  return ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createHashLockedStream",
+    funcArgs: [e.Buffer(secretHash), e.U64(expiry), e.U64(10), generateStreamSegment(10, 1, 100)],
+    value: 10,
+  });
+};
+
+const getCommitment = (wallet, secret = SECRET) => {
+  return createHash("sha256")
+    .update(Buffer.concat([Buffer.from(secret), Buffer.from(wallet.toTopBytes())]))
+    .digest("hex");
+};
+
+const commitRedeem = (wallet, ctx, streamId: number, secret = SECRET) => {
+  return wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "commitRedeem",
+    funcArgs: [e.U64(streamId), e.Buffer(getCommitment(wallet, secret))],
+    value: 0,
+  });
+};
+
+const requireCommitmentCleared = async (ctx, wallet, streamId: number) => {
+  assertAccount(await ctx.contract.getAccountWithKvs(), {
+    hasKvs: [e.kvs.Mapper("redeemCommitment", e.U64(streamId), e.Buffer(getCommitment(wallet))).Value(null)],
+  });
+};
+
+const nextBlock = (ctx, nonce: number) => ctx.world.setCurrentBlockInfo({ nonce });
+
+const redeemStream = (wallet, ctx, streamId: number, secret = SECRET) => {
+  return wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "redeemStream",
+    funcArgs: [e.U64(streamId), e.Str(secret)],
+    value: 0,
+  });
+};
+
+test("Redeem hash locked stream", async (ctx) => {
+  await createHashLockedStream(ctx, 50);
+
+  assertAccount(await ctx.contract.getAccountWithKvs(), {
+    hasKvs: [e.kvs.Esdts([{ id: ctx.stream_nft_token_identifier, nonce: 1, amount: 1 }])],
+  });
+
+  await commitRedeem(ctx.recipient_wallet, ctx, 1);
+  await nextBlock(ctx, 1);
+
+  await redeemStream(ctx.recipient_wallet, ctx, 1, "wrong-secret").assertFail({ message: ERR_INVALID_SECRET });
+  await redeemStream(ctx.sender_wallet, ctx, 1).assertFail({ message: ERR_STREAM_TO_CALLER });
+
+  await redeemStream(ctx.recipient_wallet, ctx, 1);
+
+  await requireValidStreamNft(ctx);
+  expect((await getStream(ctx, 1)).acceptance_deadline).toBe(null);
+  await requireCommitmentCleared(ctx, ctx.recipient_wallet, 1);
+
+  await redeemStream(ctx.recipient_wallet, ctx, 1).assertFail({ message: ERR_STREAM_NOT_HASH_LOCKED });
+});
+
+test("Redeem hash locked stream without commitment", async (ctx) => {
+  await createHashLockedStream(ctx, 50);
+  const frontRunner = await ctx.world.createWallet();
+
+  await redeemStream(ctx.recipient_wallet, ctx, 1).assertFail({ message: ERR_REDEEM_NOT_COMMITTED });
+
+  // The commitment must be made in a previous block
+  await commitRedeem(ctx.recipient_wallet, ctx, 1);
+  await redeemStream(ctx.recipient_wallet, ctx, 1).assertFail({ message: ERR_REDEEM_NOT_COMMITTED });
+
+  await nextBlock(ctx, 1);
+
+  // The commitment is bound to the address that made it
+  await redeemStream(frontRunner, ctx, 1).assertFail({ message: ERR_REDEEM_NOT_COMMITTED });
+
+  await redeemStream(ctx.recipient_wallet, ctx, 1);
+
+  await requireValidStreamNft(ctx);
+});
+
+test("Redeem hash locked stream after expiry", async (ctx) => {
+  await createHashLockedStream(ctx, 50);
+  await commitRedeem(ctx.recipient_wallet, ctx, 1);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 51,
+  });
+
+  await redeemStream(ctx.recipient_wallet, ctx, 1).assertFail({ message: ERR_ACCEPTANCE_DEADLINE_PASSED });
+});
+
+test("Reclaim hash locked stream after expiry", async (ctx) => {
+  await createHashLockedStream(ctx, 50);
+  await commitRedeem(ctx.recipient_wallet, ctx, 1);
+
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getStatusOf",
+    funcArgs: [e.U64(1)],
+  });
+  expect(Number(d.U8().topDecode(returnData[0]))).toBe(AWAITING_REDEEM_STATUS);
+
+  await ctx.sender_wallet
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 50_000_000,
+      funcName: "clearRedeemCommitments",
+      funcArgs: [e.U64(1)],
+      value: 0,
+    })
+    .assertFail({ message: ERR_STREAM_HASH_LOCKED });
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 51,
+  });
+
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "reclaimStream",
+    funcArgs: [e.U64(1)],
+    value: 0,
+  });
+
+  await requireEgldBalance(ctx, ctx.sender_wallet, 1000);
+  await requireStreamInvalid(ctx, 1);
+  await requireCommitmentCleared(ctx, ctx.recipient_wallet, 1);
+  assertAccount(await ctx.contract.getAccountWithKvs(), {
+    hasKvs: [e.kvs.Esdts([{ id: ctx.stream_nft_token_identifier, nonce: 1, amount: 0 }])],
+  });
+});
+
+test("Redeem stream that is not hash locked", async (ctx) => {
+  await createStream(ctx);
+
+  await redeemStream(ctx.recipient_wallet, ctx, 1).assertFail({ message: ERR_STREAM_NOT_HASH_LOCKED });
+});
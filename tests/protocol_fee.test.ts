@@ -1,8 +1,37 @@
 import { expect, test } from "vitest";
 import { d, e } from "xsuite";
 
-import { ERR_PROTOCOL_FEE_ZERO } from "./errors";
-import { generateStreamSegment, getStream } from "./utils";
+import {
+  ERR_INVALID_TREASURY_SHARES,
+  ERR_NO_PENDING_PROTOCOL_FEE,
+  ERR_NO_PENDING_PROTOCOL_FEE_DELAY,
+  ERR_NO_PROTOCOL_FEES,
+  ERR_PROTOCOL_FEE_DELAY,
+  ERR_PROTOCOL_FEE_DELAY_DECREASE,
+  ERR_PROTOCOL_FEE_DELAY_TOO_BIG,
+  ERR_PROTOCOL_FEE_NOT_ANNOUNCED,
+  ERR_PROTOCOL_FEE_TOO_BIG,
+  ERR_PROTOCOL_FEE_ZERO,
+} from "./errors";
+import { generateStreamSegment, getStream, requireEgldBalance } from "./utils";
+
+const createStreamWithProtocolFee = async (ctx) => {
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setProtocolFee",
+    funcArgs: [e.Str("EGLD"), e.U(10_00n)],
+    value: 0,
+  });
+
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [ctx.recipient_wallet, generateStreamSegment(90, 1, 1000)],
+    value: 100,
+  });
+};
 
 test("Set protocol fee", async (ctx) => {
   await ctx.deployer.callContract({
@@ -36,6 +65,18 @@ test("Set invalid protocol fee", async (ctx) => {
     .assertFail({ message: ERR_PROTOCOL_FEE_ZERO });
 });
 
+test("Set protocol fee above cap", async (ctx) => {
+  await ctx.deployer
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 10_000_000,
+      funcName: "setProtocolFee",
+      funcArgs: [e.Str("EGLD"), e.U(10_01n)],
+      value: 0,
+    })
+    .assertFail({ message: ERR_PROTOCOL_FEE_TOO_BIG });
+});
+
 test("Remove protocol fee", async (ctx) => {
   await ctx.deployer.callContract({
     callee: ctx.contract,
@@ -121,3 +162,349 @@ test("Stream created with protocol fee", async (ctx) => {
 
   expect(stream.deposit).toBe(9n);
 });
+
+test("Protocol fee is accrued", async (ctx) => {
+  await createStreamWithProtocolFee(ctx);
+
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getAccruedProtocolFee",
+    funcArgs: [e.Str("EGLD"), e.U64(0)],
+  });
+
+  expect(d.U().topDecode(returnData[0])).toBe(10n);
+  await requireEgldBalance(ctx, ctx.deployer, 0);
+});
+
+test("Withdraw protocol fees to owner", async (ctx) => {
+  await createStreamWithProtocolFee(ctx);
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "withdrawProtocolFees",
+    funcArgs: [],
+    value: 0,
+  });
+
+  await requireEgldBalance(ctx, ctx.deployer, 10);
+
+  await ctx.deployer
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 10_000_000,
+      funcName: "withdrawProtocolFees",
+      funcArgs: [],
+      value: 0,
+    })
+    .assertFail({ message: ERR_NO_PROTOCOL_FEES });
+});
+
+test("Withdraw protocol fees to treasury", async (ctx) => {
+  const firstTreasury = await ctx.world.createWallet();
+  const secondTreasury = await ctx.world.createWallet();
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setProtocolFeeTreasury",
+    funcArgs: [firstTreasury, e.U64(33_33), secondTreasury, e.U64(66_67)],
+    value: 0,
+  });
+
+  await createStreamWithProtocolFee(ctx);
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "withdrawProtocolFees",
+    funcArgs: [e.Str("EGLD"), e.U64(0)],
+    value: 0,
+  });
+
+  await requireEgldBalance(ctx, firstTreasury, 3);
+  await requireEgldBalance(ctx, secondTreasury, 7);
+});
+
+test("Withdraw protocol fees with duplicated tokens", async (ctx) => {
+  await createStreamWithProtocolFee(ctx);
+
+  await ctx.deployer
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 10_000_000,
+      funcName: "withdrawProtocolFees",
+      funcArgs: [e.Str("EGLD"), e.U64(0), e.Str("EGLD"), e.U64(0)],
+      value: 0,
+    })
+    .assertFail({ message: ERR_NO_PROTOCOL_FEES });
+
+  await requireEgldBalance(ctx, ctx.deployer, 0);
+
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getAccruedProtocolFee",
+    funcArgs: [e.Str("EGLD"), e.U64(0)],
+  });
+  expect(d.U().topDecode(returnData[0])).toBe(10n);
+});
+
+test("Set invalid protocol fee treasury", async (ctx) => {
+  await ctx.deployer
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 10_000_000,
+      funcName: "setProtocolFeeTreasury",
+      funcArgs: [ctx.sender_wallet, e.U64(50_00)],
+      value: 0,
+    })
+    .assertFail({ message: ERR_INVALID_TREASURY_SHARES });
+});
+
+const getProtocolFee = async (ctx) => {
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getProtocolFee",
+    funcArgs: [e.Str("EGLD")],
+  });
+
+  return d.U().topDecode(returnData[0]);
+};
+
+test("Announce and apply protocol fee", async (ctx) => {
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setProtocolFeeDelay",
+    funcArgs: [e.U64(1000)],
+    value: 0,
+  });
+
+  await ctx.deployer
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 10_000_000,
+      funcName: "setProtocolFee",
+      funcArgs: [e.Str("EGLD"), e.U(5_00n)],
+      value: 0,
+    })
+    .assertFail({ message: ERR_PROTOCOL_FEE_NOT_ANNOUNCED });
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "announceProtocolFee",
+    funcArgs: [e.Str("EGLD"), e.U(5_00n)],
+    value: 0,
+  });
+
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getPendingProtocolFee",
+    funcArgs: [e.Str("EGLD")],
+  });
+  expect(d.Tuple({ value: d.U(), apply_after: d.U64() }).topDecode(returnData[0])).toEqual({
+    value: 5_00n,
+    apply_after: 1000n,
+  });
+
+  await ctx.deployer
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 10_000_000,
+      funcName: "applyProtocolFee",
+      funcArgs: [e.Str("EGLD")],
+      value: 0,
+    })
+    .assertFail({ message: ERR_PROTOCOL_FEE_DELAY });
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 1000,
+  });
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "applyProtocolFee",
+    funcArgs: [e.Str("EGLD")],
+    value: 0,
+  });
+
+  expect(await getProtocolFee(ctx)).toBe(5_00n);
+
+  // Decreasing the fee doesn't need to be announced
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setProtocolFee",
+    funcArgs: [e.Str("EGLD"), e.U(2_00n)],
+    value: 0,
+  });
+
+  expect(await getProtocolFee(ctx)).toBe(2_00n);
+});
+
+test("Decrease protocol fee delay", async (ctx) => {
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setProtocolFeeDelay",
+    funcArgs: [e.U64(1000)],
+    value: 0,
+  });
+
+  await ctx.deployer
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 10_000_000,
+      funcName: "setProtocolFeeDelay",
+      funcArgs: [e.U64(10)],
+      value: 0,
+    })
+    .assertFail({ message: ERR_PROTOCOL_FEE_DELAY_DECREASE });
+
+  await ctx.deployer
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 10_000_000,
+      funcName: "applyProtocolFeeDelay",
+      value: 0,
+    })
+    .assertFail({ message: ERR_NO_PENDING_PROTOCOL_FEE_DELAY });
+
+  // A decrease waits for the current delay
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "announceProtocolFeeDelay",
+    funcArgs: [e.U64(10)],
+    value: 0,
+  });
+
+  await ctx.deployer
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 10_000_000,
+      funcName: "applyProtocolFeeDelay",
+      value: 0,
+    })
+    .assertFail({ message: ERR_PROTOCOL_FEE_DELAY });
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 1000,
+  });
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "applyProtocolFeeDelay",
+    value: 0,
+  });
+
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getProtocolFeeDelay",
+  });
+  expect(d.U().topDecode(returnData[0])).toBe(10n);
+});
+
+test("Cancel protocol fee delay decrease", async (ctx) => {
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setProtocolFeeDelay",
+    funcArgs: [e.U64(1000)],
+    value: 0,
+  });
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "announceProtocolFeeDelay",
+    funcArgs: [e.U64(0)],
+    value: 0,
+  });
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "cancelProtocolFeeDelay",
+    value: 0,
+  });
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 1000,
+  });
+
+  await ctx.deployer
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 10_000_000,
+      funcName: "applyProtocolFeeDelay",
+      value: 0,
+    })
+    .assertFail({ message: ERR_NO_PENDING_PROTOCOL_FEE_DELAY });
+});
+
+test("Set protocol fee delay above the maximum", async (ctx) => {
+  await ctx.deployer
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 10_000_000,
+      funcName: "setProtocolFeeDelay",
+      funcArgs: [e.U64(30 * 24 * 60 * 60 + 1)],
+      value: 0,
+    })
+    .assertFail({ message: ERR_PROTOCOL_FEE_DELAY_TOO_BIG });
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setProtocolFeeDelay",
+    funcArgs: [e.U64(30 * 24 * 60 * 60)],
+    value: 0,
+  });
+});
+
+test("Set protocol fee drops the pending announcement", async (ctx) => {
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setProtocolFeeDelay",
+    funcArgs: [e.U64(1000)],
+    value: 0,
+  });
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "announceProtocolFee",
+    funcArgs: [e.Str("EGLD"), e.U(5_00n)],
+    value: 0,
+  });
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "removeProtocolFee",
+    funcArgs: [e.Str("EGLD")],
+    value: 0,
+  });
+
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getPendingProtocolFee",
+    funcArgs: [e.Str("EGLD")],
+  });
+  expect(returnData[0]).toBe("");
+
+  await ctx.deployer
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 10_000_000,
+      funcName: "applyProtocolFee",
+      funcArgs: [e.Str("EGLD")],
+      value: 0,
+    })
+    .assertFail({ message: ERR_NO_PENDING_PROTOCOL_FEE });
+});
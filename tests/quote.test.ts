@@ -0,0 +1,93 @@
+import { expect, test } from "vitest";
+import { d, e } from "xsuite";
+
+import { ERR_BROKER_FEE_TOO_BIG, ERR_INVALID_SEGMENTS_DEPOSIT, ERR_START_TIME } from "./errors";
+import { generateStreamSegment } from "./utils";
+
+const quoteDecoder = d.Tuple({
+  protocol_fee: d.U(),
+  broker_fee: d.U(),
+  deposit: d.U(),
+  end_time: d.U64(),
+  error: d.Option(d.Str()),
+});
+
+const quoteCreateStream = async (ctx, funcArgs) => {
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "quoteCreateStream",
+    funcArgs,
+  });
+
+  return quoteDecoder.topDecode(returnData[0]);
+};
+
+test("Quote stream with fees", async (ctx) => {
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setProtocolFee",
+    funcArgs: [e.Str("EGLD"), e.U(10_00n)],
+    value: 0,
+  });
+
+  const quote = await quoteCreateStream(ctx, [
+    e.Str("EGLD"),
+    e.U(1000),
+    e.U64(100),
+    e.List(generateStreamSegment(810, 1, 500)),
+    e.U64(0),
+    e.Tuple(e.Addr(ctx.deployer.toTopBytes()), e.U(10_00)),
+  ]);
+
+  expect(quote).toEqual({
+    protocol_fee: 100n,
+    broker_fee: 90n,
+    deposit: 810n,
+    end_time: 600n,
+    error: null,
+  });
+});
+
+test("Quote stream with invalid segments", async (ctx) => {
+  const quote = await quoteCreateStream(ctx, [
+    e.Str("EGLD"),
+    e.U(1000),
+    e.U64(0),
+    e.List(generateStreamSegment(900, 1, 500)),
+    e.U64(0),
+  ]);
+
+  expect(quote.deposit).toBe(1000n);
+  expect(quote.error).toBe(ERR_INVALID_SEGMENTS_DEPOSIT);
+});
+
+test("Quote stream returns the first error", async (ctx) => {
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 100,
+  });
+
+  const quote = await quoteCreateStream(ctx, [
+    e.Str("EGLD"),
+    e.U(1000),
+    e.U64(50),
+    e.List(generateStreamSegment(900, 1, 500)),
+    e.U64(0),
+    e.Tuple(e.Addr(ctx.deployer.toTopBytes()), e.U(20_00)),
+  ]);
+
+  expect(quote.error).toBe(ERR_START_TIME);
+});
+
+test("Quote stream with invalid broker fee", async (ctx) => {
+  const quote = await quoteCreateStream(ctx, [
+    e.Str("EGLD"),
+    e.U(1000),
+    e.U64(0),
+    e.List(generateStreamSegment(800, 1, 500)),
+    e.U64(0),
+    e.Tuple(e.Addr(ctx.deployer.toTopBytes()), e.U(20_00)),
+  ]);
+
+  expect(quote.error).toBe(ERR_BROKER_FEE_TOO_BIG);
+});
@@ -0,0 +1,103 @@
+import { expect, test } from "vitest";
+import { assertAccount, e } from "xsuite";
+
+import { ERR_STREAM_TO_CALLER } from "./errors";
+import { generateStreamSegment, getStream } from "./utils";
+
+const createEsdtStream = (ctx, segmentAmount = 1000) => {
+  return ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [ctx.recipient_wallet, generateStreamSegment(segmentAmount, 1, 100)],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.payment_esdt_token_identifier,
+        nonce: 0,
+        amount: 1000,
+      },
+    ],
+  });
+};
+
+const restream = (ctx, recipient) => {
+  return ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "restream",
+    funcArgs: [e.U64(1), recipient, e.U64(50), e.List(generateStreamSegment(1000, 1, 100))],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.stream_nft_token_identifier,
+        nonce: 1,
+        amount: 1,
+      },
+    ],
+  });
+};
+
+test("Restream the claimable balance", async (ctx) => {
+  const contributor = await ctx.world.createWallet();
+  await createEsdtStream(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  await restream(ctx, contributor);
+
+  const parentStream = await getStream(ctx, 1);
+  expect(parentStream.claimed_amount).toBe(500n);
+
+  const stream = await getStream(ctx, 2);
+  expect(stream.sender).toBe(ctx.recipient_wallet.toString());
+  expect(stream.deposit).toBe(500n);
+  expect(stream.segments[0].amount).toBe(500n);
+  expect(stream.start_time).toBe(50n);
+  expect(stream.end_time).toBe(150n);
+
+  assertAccount(await contributor.getAccountWithKvs(), {
+    hasKvs: [e.kvs.Esdts([{ id: ctx.stream_nft_token_identifier, nonce: 2, amount: 1 }])],
+  });
+  assertAccount(await ctx.recipient_wallet.getAccountWithKvs(), {
+    hasKvs: [e.kvs.Esdts([{ id: ctx.stream_nft_token_identifier, nonce: 1, amount: 1 }])],
+  });
+});
+
+test("Restream with protocol fee", async (ctx) => {
+  const contributor = await ctx.world.createWallet();
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setProtocolFee",
+    funcArgs: [e.Str(ctx.payment_esdt_token_identifier), e.U(10_00n)],
+    value: 0,
+  });
+  await createEsdtStream(ctx, 900);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  await restream(ctx, contributor);
+
+  // The parent stream deposit is 900 after the fee, so 450 is claimable at half of the stream
+  const stream = await getStream(ctx, 2);
+  expect(stream.deposit).toBe(405n);
+  expect(stream.segments[0].amount).toBe(405n);
+});
+
+test("Restream to the caller", async (ctx) => {
+  await createEsdtStream(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  await restream(ctx, ctx.recipient_wallet).assertFail({ message: ERR_STREAM_TO_CALLER });
+
+  const parentStream = await getStream(ctx, 1);
+  expect(parentStream.claimed_amount).toBe(0n);
+});
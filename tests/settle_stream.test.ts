@@ -0,0 +1,196 @@
+import { expect, test } from "vitest";
+import { d, e } from "xsuite";
+
+import { ERR_CANT_SETTLE, ERR_INVALID_STREAM, ERR_STREAM_NFT_NOT_IN_CUSTODY } from "./errors";
+import {
+  createStream,
+  generateStreamSegment,
+  requireEgldBalance,
+  requireStreamInvalid,
+  requireValidStreamNft,
+} from "./utils";
+
+const settleStream = (wallet, ctx, streamId = 1) => {
+  return wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "settleStream",
+    funcArgs: [e.U64(streamId)],
+    value: 0,
+  });
+};
+
+const depositStreamNft = (ctx, streamId = 1) => {
+  return ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "depositStreamNft",
+    funcArgs: [e.U64(streamId)],
+    value: 0,
+    esdts: [{ id: ctx.stream_nft_token_identifier, nonce: streamId, amount: 1 }],
+  });
+};
+
+test("Settle stream", async (ctx) => {
+  const keeper = await ctx.world.createWallet();
+  await createStream(ctx, 600);
+  await depositStreamNft(ctx);
+
+  await settleStream(keeper, ctx).assertFail({ message: ERR_CANT_SETTLE });
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 700,
+  });
+
+  await settleStream(keeper, ctx);
+
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 10);
+  await requireEgldBalance(ctx, keeper, 0);
+  await requireValidStreamNft(ctx, 0);
+  await requireStreamInvalid(ctx, 1);
+
+  await settleStream(keeper, ctx).assertFail({ message: ERR_INVALID_STREAM });
+});
+
+test("Settle stream with keeper fee", async (ctx) => {
+  const keeper = await ctx.world.createWallet();
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setProtocolFee",
+    funcArgs: [e.Str("EGLD"), e.U(10_00n)],
+    value: 0,
+  });
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setKeeperFee",
+    funcArgs: [e.Str("EGLD"), e.U(1)],
+    value: 0,
+  });
+
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [ctx.recipient_wallet, generateStreamSegment(18, 1, 600)],
+    value: 20,
+  });
+  await depositStreamNft(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 700,
+  });
+
+  await settleStream(keeper, ctx);
+
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 18);
+  await requireEgldBalance(ctx, keeper, 1);
+
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getAccruedProtocolFee",
+    funcArgs: [e.Str("EGLD"), e.U64(0)],
+  });
+  expect(d.U().topDecode(returnData[0])).toBe(1n);
+});
+
+test("Settle streams with keeper fee bounded by their protocol fee", async (ctx) => {
+  const keeper = await ctx.world.createWallet();
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setProtocolFee",
+    funcArgs: [e.Str("EGLD"), e.U(10_00n)],
+    value: 0,
+  });
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setKeeperFee",
+    funcArgs: [e.Str("EGLD"), e.U(5)],
+    value: 0,
+  });
+
+  // Protocol fees of 2 and 10
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [ctx.recipient_wallet, generateStreamSegment(18, 1, 600)],
+    value: 20,
+  });
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [ctx.recipient_wallet, generateStreamSegment(90, 1, 600)],
+    value: 100,
+  });
+  await depositStreamNft(ctx, 1);
+  await depositStreamNft(ctx, 2);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 700,
+  });
+
+  await settleStream(keeper, ctx, 1);
+  await settleStream(keeper, ctx, 1).assertFail({ message: ERR_INVALID_STREAM });
+
+  await requireEgldBalance(ctx, keeper, 2);
+
+  await settleStream(keeper, ctx, 2);
+  await settleStream(keeper, ctx, 2).assertFail({ message: ERR_INVALID_STREAM });
+
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 108);
+  await requireEgldBalance(ctx, keeper, 7);
+
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getAccruedProtocolFee",
+    funcArgs: [e.Str("EGLD"), e.U64(0)],
+  });
+  expect(d.U().topDecode(returnData[0])).toBe(5n);
+});
+
+test("Settle stream not in custody", async (ctx) => {
+  await createStream(ctx, 600);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 700,
+  });
+
+  await settleStream(ctx.sender_wallet, ctx).assertFail({ message: ERR_STREAM_NFT_NOT_IN_CUSTODY });
+
+  // The stream is still claimable by the holder of the NFT
+  await ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "claimFromStream",
+    funcArgs: [e.U64(1)],
+    value: 0,
+    esdts: [{ id: ctx.stream_nft_token_identifier, nonce: 1, amount: 1 }],
+  });
+
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 10);
+  await requireStreamInvalid(ctx, 1);
+});
+
+test("Settle stream with transferred NFT", async (ctx) => {
+  const newHolder = await ctx.world.createWallet();
+  await createStream(ctx, 600);
+
+  await ctx.recipient_wallet.transfer({
+    receiver: newHolder,
+    gasLimit: 10_000_000,
+    esdts: [{ id: ctx.stream_nft_token_identifier, nonce: 1, amount: 1 }],
+  });
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 700,
+  });
+
+  await settleStream(ctx.sender_wallet, ctx).assertFail({ message: ERR_STREAM_NFT_NOT_IN_CUSTODY });
+});
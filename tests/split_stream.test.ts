@@ -0,0 +1,247 @@
+import { expect, test } from "vitest";
+import { assertAccount, d, e } from "xsuite";
+
+import { ERR_CANT_SPLIT, ERR_INVALID_SPLIT_SHARES, ERR_SPLIT_ABOVE_STREAMED } from "./errors";
+import {
+  cancelStream,
+  claimFromStream,
+  generateStreamSegment,
+  getRecipientBalance,
+  getStream,
+  requireStreamInvalid,
+} from "./utils";
+
+const createEsdtStream = (ctx) => {
+  return ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [ctx.recipient_wallet, generateStreamSegment(1000, 1, 100)],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.payment_esdt_token_identifier,
+        nonce: 0,
+        amount: 1000,
+      },
+    ],
+  });
+};
+
+const splitStream = (ctx, shares: number[]) => {
+  return ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 300_000_000,
+    funcName: "splitStream",
+    funcArgs: [e.U64(1), ...shares.map((share) => e.U64(share))],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.stream_nft_token_identifier,
+        nonce: 1,
+        amount: 1,
+      },
+    ],
+  });
+};
+
+test("Split stream", async (ctx) => {
+  await createEsdtStream(ctx);
+
+  const { returnData } = await splitStream(ctx, [1, 1, 1]);
+  expect(returnData.map((id) => d.U64().topDecode(id))).toEqual([2n, 3n, 4n]);
+
+  await requireStreamInvalid(ctx, 1);
+
+  const deposits: bigint[] = [];
+  for (const streamId of [2, 3, 4]) {
+    const stream = await getStream(ctx, streamId);
+    expect(stream.sender).toBe(ctx.sender_wallet.toString());
+    expect(stream.start_time).toBe(0n);
+    expect(stream.end_time).toBe(100n);
+    expect(stream.can_cancel).toBe(true);
+    expect(stream.segments[0].amount).toBe(stream.deposit);
+    deposits.push(stream.deposit);
+  }
+
+  // The rounding dust goes to the last stream
+  expect(deposits).toEqual([333n, 333n, 334n]);
+
+  assertAccount(await ctx.recipient_wallet.getAccountWithKvs(), {
+    hasKvs: [
+      e.kvs.Esdts([
+        { id: ctx.stream_nft_token_identifier, nonce: 2, amount: 1 },
+        { id: ctx.stream_nft_token_identifier, nonce: 3, amount: 1 },
+        { id: ctx.stream_nft_token_identifier, nonce: 4, amount: 1 },
+      ]),
+    ],
+  });
+});
+
+test("Split stream with claimed amount", async (ctx) => {
+  await createEsdtStream(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 30,
+  });
+  await claimFromStream(ctx, 1);
+
+  // The streams of 333 and 667 would have streamed 99 and 200 of the claimed 300
+  await splitStream(ctx, [1, 2]).assertFail({ message: ERR_SPLIT_ABOVE_STREAMED });
+
+  await splitStream(ctx, [1, 1]);
+
+  for (const streamId of [2, 3]) {
+    const stream = await getStream(ctx, streamId);
+    expect(stream.claimed_amount).toBe(150n);
+    expect(stream.deposit).toBe(500n);
+    expect(stream.cliff_amount).toBe(0n);
+    expect(stream.segments[0].amount).toBe(500n);
+  }
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 200,
+  });
+
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "recipientBalance",
+    funcArgs: [e.U64(3)],
+  });
+  expect(d.U().topDecode(returnData[0])).toBe(350n);
+});
+
+test("Split stream with multiple segments and cliff amount", async (ctx) => {
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [
+      ctx.recipient_wallet,
+      e.List(generateStreamSegment(300, 1, 40), generateStreamSegment(600, 2, 60)),
+      e.U64(10),
+      e.Bool(true),
+      e.List(),
+      e.U(100),
+    ],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.payment_esdt_token_identifier,
+        nonce: 0,
+        amount: 1000,
+      },
+    ],
+  });
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 40,
+  });
+  await claimFromStream(ctx, 1);
+  expect((await getStream(ctx, 1)).claimed_amount).toBe(400n);
+
+  await splitStream(ctx, [1, 2, 4]);
+
+  // The cliff amount and every segment are split on their own, the rounding dust going to the last stream
+  const streams = await Promise.all([2, 3, 4].map((streamId) => getStream(ctx, streamId)));
+  expect(streams.map((stream) => stream.cliff_amount)).toEqual([14n, 28n, 58n]);
+  expect(streams.map((stream) => stream.segments[0].amount)).toEqual([42n, 85n, 173n]);
+  expect(streams.map((stream) => stream.segments[1].amount)).toEqual([85n, 171n, 344n]);
+  expect(streams.map((stream) => stream.deposit)).toEqual([141n, 284n, 575n]);
+
+  // The claimed shares of 57 and 114 are capped by the 56 and 113 streamed so far
+  expect(streams.map((stream) => stream.claimed_amount)).toEqual([56n, 113n, 231n]);
+
+  for (const stream of streams) {
+    expect(stream.segments.map((segment) => segment.duration)).toEqual([40n, 60n]);
+    expect(stream.segments.map((segment) => Number(segment.exponent))).toEqual([1, 2]);
+    expect(stream.cliff).toBe(10n);
+  }
+  for (const streamId of [2, 3, 4]) {
+    expect(await getRecipientBalance(ctx, streamId)).toBe(0n);
+  }
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 200,
+  });
+
+  let totalBalance = 0n;
+  for (const streamId of [2, 3, 4]) {
+    totalBalance += await getRecipientBalance(ctx, streamId);
+  }
+  expect(totalBalance).toBe(600n);
+});
+
+test("Split stream carries over the protocol fee and the claim destination", async (ctx) => {
+  const destination = await ctx.world.createWallet();
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setProtocolFee",
+    funcArgs: [e.Str(ctx.payment_esdt_token_identifier), e.U(10_00n)],
+    value: 0,
+  });
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [ctx.recipient_wallet, generateStreamSegment(900, 1, 100)],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.payment_esdt_token_identifier,
+        nonce: 0,
+        amount: 1000,
+      },
+    ],
+  });
+  await ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "setClaimDestination",
+    funcArgs: [e.U64(1), destination],
+    value: 0,
+    esdts: [{ id: ctx.stream_nft_token_identifier, nonce: 1, amount: 1 }],
+  });
+
+  await splitStream(ctx, [1, 1, 2]);
+
+  const protocolFees: bigint[] = [];
+  for (const streamId of [2, 3, 4]) {
+    const { returnData } = await ctx.world.query({
+      callee: ctx.contract,
+      funcName: "getStreamProtocolFee",
+      funcArgs: [e.U64(streamId)],
+    });
+    protocolFees.push(d.U().topDecode(returnData[0]));
+  }
+  expect(protocolFees).toEqual([25n, 25n, 50n]);
+
+  assertAccount(await ctx.contract.getAccountWithKvs(), {
+    hasKvs: [
+      e.kvs.Mapper("streamProtocolFee", e.U64(1)).Value(null),
+      e.kvs.Mapper("claimDestination", e.U64(1)).Value(null),
+      e.kvs.Mapper("claimDestination", e.U64(2)).Value(e.Tuple(ctx.recipient_wallet, destination)),
+      e.kvs.Mapper("claimDestination", e.U64(4)).Value(e.Tuple(ctx.recipient_wallet, destination)),
+    ],
+  });
+});
+
+test("Split stream with invalid shares", async (ctx) => {
+  await createEsdtStream(ctx);
+
+  await splitStream(ctx, [1]).assertFail({ message: ERR_INVALID_SPLIT_SHARES });
+  await splitStream(ctx, [1, 0]).assertFail({ message: ERR_INVALID_SPLIT_SHARES });
+});
+
+test("Split canceled stream", async (ctx) => {
+  await createEsdtStream(ctx);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 30,
+  });
+  await cancelStream(ctx, 1, true, false);
+
+  await splitStream(ctx, [1, 1]).assertFail({ message: ERR_CANT_SPLIT });
+});
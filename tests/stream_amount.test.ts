@@ -1,13 +1,19 @@
 import { expect, test } from "vitest";
 import { assertAccount, e } from "xsuite";
 
-import { ERR_ZERO_CLAIM } from "./errors";
+import {
+  ERR_SEGMENT_EXPONENT_DENOMINATOR_TOO_BIG,
+  ERR_SEGMENT_EXPONENT_DENOMINATOR_ZERO,
+  ERR_SEGMENT_EXPONENT_TOO_BIG,
+  ERR_ZERO_CLAIM,
+} from "./errors";
 import { PAYMENT_ESDT_TOKEN_IDENTIFIER_ROUNDING } from "./setup";
 import {
   claimFromStream,
   createStream,
   generateStreamSegment,
   getRecipientBalance,
+  getStream,
   requireStreamInvalid,
   requireValidStreamNft,
 } from "./utils";
@@ -132,3 +138,162 @@ test("Rounding test 2", async (ctx) => {
 
   await requireValidStreamNft(ctx);
 });
+
+test("Recipient balance with rational exponent", async (ctx) => {
+  const result = await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [ctx.recipient_wallet, generateStreamSegment(100, 1, 100, 2)],
+    value: 100,
+  });
+
+  const streamId = parseInt(result.returnData[0]);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 25,
+  });
+
+  expect(await getRecipientBalance(ctx, streamId)).toBe(50n);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 64,
+  });
+
+  expect(await getRecipientBalance(ctx, streamId)).toBe(80n);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 100,
+  });
+
+  expect(await getRecipientBalance(ctx, streamId)).toBe(100n);
+});
+
+test("Segment with zero exponent denominator", async (ctx) => {
+  await ctx.sender_wallet
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 200_000_000,
+      funcName: "createStreamNow",
+      funcArgs: [ctx.recipient_wallet, generateStreamSegment(100, 1, 100, 0)],
+      value: 100,
+    })
+    .assertFail({ message: ERR_SEGMENT_EXPONENT_DENOMINATOR_ZERO });
+});
+
+test("Segment with maximum exponent and exponent denominator", async (ctx) => {
+  const result = await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [ctx.recipient_wallet, generateStreamSegment(100, 10, 100, 10)],
+    value: 100,
+  });
+
+  const streamId = parseInt(result.returnData[0]);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  expect(await getRecipientBalance(ctx, streamId)).toBe(50n);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 100,
+  });
+
+  expect(await getRecipientBalance(ctx, streamId)).toBe(100n);
+});
+
+test("Segment with rational exponent above the maximum", async (ctx) => {
+  await ctx.sender_wallet
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 200_000_000,
+      funcName: "createStreamNow",
+      funcArgs: [ctx.recipient_wallet, generateStreamSegment(100, 11, 100, 2)],
+      value: 100,
+    })
+    .assertFail({ message: ERR_SEGMENT_EXPONENT_TOO_BIG });
+});
+
+test("Segment with integer exponent above the rational maximum", async (ctx) => {
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [ctx.recipient_wallet, generateStreamSegment(100, 11, 100)],
+    value: 100,
+  });
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  // 100 * 50^11 / 100^11
+  expect(await getRecipientBalance(ctx, 1)).toBe(0n);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 100,
+  });
+
+  expect(await getRecipientBalance(ctx, 1)).toBe(100n);
+});
+
+test("Stream stored with the legacy layout", async (ctx) => {
+  // Layout of the streams stored before rational segment exponents, without a layout version
+  const legacyStream = e.Tuple(
+    e.Addr(ctx.sender_wallet.toTopBytes()),
+    e.U64(1),
+    e.Str("EGLD"),
+    e.U64(0),
+    e.U(100),
+    e.U(0),
+    e.Bool(true),
+    e.U64(0),
+    e.U64(100),
+    e.U64(0),
+    e.List(e.Tuple(e.U(100), e.U32(1), e.U64(100))),
+    e.Option(null)
+  );
+
+  await ctx.contract.setAccount({
+    ...(await ctx.contract.getAccount()),
+    kvs: [
+      (await ctx.contract.getAccountWithKvs()).kvs,
+      e.kvs.Mapper("lastStreamId").Value(e.U64(1)),
+      e.kvs.Mapper("streamById", e.U64(1)).Value(legacyStream),
+    ],
+  });
+
+  const stream = await getStream(ctx, 1);
+  expect(stream.sender).toBe(ctx.sender_wallet.toString());
+  expect(stream.deposit).toBe(100n);
+  expect(stream.end_time).toBe(100n);
+  expect(stream.cliff_amount).toBe(0n);
+  expect(stream.segments.length).toBe(1);
+  expect(stream.segments[0].amount).toBe(100n);
+  expect(Number(stream.segments[0].exponent)).toBe(1);
+  expect(Number(stream.segments[0].exponent_denominator)).toBe(1);
+  expect(stream.segments[0].duration).toBe(100n);
+  expect(stream.balances_after_cancel).toBe(null);
+  expect(stream.acceptance_deadline).toBe(null);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 50,
+  });
+
+  expect(await getRecipientBalance(ctx, 1)).toBe(50n);
+});
+
+test("Segment with exponent denominator above the maximum", async (ctx) => {
+  await ctx.sender_wallet
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 200_000_000,
+      funcName: "createStreamNow",
+      funcArgs: [ctx.recipient_wallet, generateStreamSegment(100, 1, 100, 11)],
+      value: 100,
+    })
+    .assertFail({ message: ERR_SEGMENT_EXPONENT_DENOMINATOR_TOO_BIG });
+});
@@ -0,0 +1,178 @@
+import { expect, test } from "vitest";
+import { d, e } from "xsuite";
+
+import { ERR_SWAP_ADAPTER_INVALID_STEPS, ERR_SWAP_ADAPTER_NOT_FOUND } from "./errors";
+import { generateStreamSegment, getStream } from "./utils";
+
+const ASH_AGGREGATOR = 0;
+const XEXCHANGE_ROUTER = 1;
+const SINGLE_POOL = 2;
+
+const swapAdapterDecoder = d.Tuple({
+  adapter_type: d.U8(),
+  address: d.Addr(),
+});
+
+const addSwapAdapter = (ctx, adapterType: number, wallet = ctx.deployer) => {
+  return wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "addSwapAdapter",
+    funcArgs: [e.U8(adapterType), ctx.deployer],
+    value: 0,
+  });
+};
+
+const getSwapAdapter = async (ctx, adapterId: number) => {
+  const { returnData } = await ctx.world.query({
+    callee: ctx.contract,
+    funcName: "getSwapAdapter",
+    funcArgs: [e.U64(adapterId)],
+  });
+
+  return returnData[0] ? swapAdapterDecoder.topDecode(returnData[0]) : undefined;
+};
+
+const generateSwapStep = (ctx, tokenIn: string, tokenOut: string, amountIn: number) => {
+  return e.Tuple(
+    e.Str(tokenIn),
+    e.Str(tokenOut),
+    e.U(amountIn),
+    e.Addr(ctx.deployer.toTopBytes()),
+    e.Str("swap"),
+    e.List()
+  );
+};
+
+const createStreamAndSettle = async (ctx) => {
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 200_000_000,
+    funcName: "createStreamNow",
+    funcArgs: [ctx.recipient_wallet, generateStreamSegment(1000, 1, 100)],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.payment_esdt_token_identifier,
+        nonce: 0,
+        amount: 1000,
+      },
+    ],
+  });
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 200,
+  });
+};
+
+const claimFromStreamSwapWithAdapter = (ctx, adapterId: number, steps: any[]) => {
+  const limits = [e.Tuple(e.Str(ctx.payment_esdt_token_identifier), e.U(500))];
+  for (let i = 0; i < steps.length; i++) {
+    limits.push(e.Tuple(e.Str("USDC-c76f1f"), e.U(1)));
+  }
+
+  return ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 100_000_000,
+    funcName: "claimFromStreamSwapWithAdapter",
+    funcArgs: [e.U64(1), e.U64(adapterId), e.List(...steps), e.List(...limits)],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.stream_nft_token_identifier,
+        nonce: 1,
+        amount: 1,
+      },
+    ],
+  });
+};
+
+test("Add swap adapters", async (ctx) => {
+  const { returnData } = await addSwapAdapter(ctx, ASH_AGGREGATOR);
+  expect(d.U64().topDecode(returnData[0])).toBe(1n);
+  await addSwapAdapter(ctx, XEXCHANGE_ROUTER);
+
+  expect(await getSwapAdapter(ctx, 1)).toEqual({
+    adapter_type: BigInt(ASH_AGGREGATOR),
+    address: ctx.deployer.toString(),
+  });
+  expect((await getSwapAdapter(ctx, 2))?.adapter_type).toBe(BigInt(XEXCHANGE_ROUTER));
+});
+
+test("Add swap adapter not owner", async (ctx) => {
+  await addSwapAdapter(ctx, SINGLE_POOL, ctx.sender_wallet).assertFail({
+    message: "Endpoint can only be called by owner",
+  });
+});
+
+test("Update swap adapter", async (ctx) => {
+  await addSwapAdapter(ctx, SINGLE_POOL);
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "updateSwapAdapter",
+    funcArgs: [e.U64(1), ctx.sender_wallet],
+    value: 0,
+  });
+
+  expect(await getSwapAdapter(ctx, 1)).toEqual({
+    adapter_type: BigInt(SINGLE_POOL),
+    address: ctx.sender_wallet.toString(),
+  });
+});
+
+test("Remove swap adapter", async (ctx) => {
+  await addSwapAdapter(ctx, SINGLE_POOL);
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "removeSwapAdapter",
+    funcArgs: [e.U64(1)],
+    value: 0,
+  });
+
+  expect(await getSwapAdapter(ctx, 1)).toBeUndefined();
+
+  await ctx.deployer
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 10_000_000,
+      funcName: "removeSwapAdapter",
+      funcArgs: [e.U64(1)],
+      value: 0,
+    })
+    .assertFail({ message: ERR_SWAP_ADAPTER_NOT_FOUND });
+});
+
+test("Claim and swap with an unknown adapter", async (ctx) => {
+  await createStreamAndSettle(ctx);
+
+  await claimFromStreamSwapWithAdapter(ctx, 1, [
+    generateSwapStep(ctx, ctx.payment_esdt_token_identifier, "USDC-c76f1f", 500),
+  ]).assertFail({ message: ERR_SWAP_ADAPTER_NOT_FOUND });
+});
+
+test("Claim and swap with a single pool adapter and multiple steps", async (ctx) => {
+  await addSwapAdapter(ctx, SINGLE_POOL);
+  await createStreamAndSettle(ctx);
+
+  await claimFromStreamSwapWithAdapter(ctx, 1, [
+    generateSwapStep(ctx, ctx.payment_esdt_token_identifier, "WEGLD-bd4d79", 500),
+    generateSwapStep(ctx, "WEGLD-bd4d79", "USDC-c76f1f", 0),
+  ]).assertFail({ message: ERR_SWAP_ADAPTER_INVALID_STEPS });
+
+  const stream = await getStream(ctx, 1);
+  expect(stream.claimed_amount).toBe(0n);
+});
+
+test("Claim and swap with an xExchange adapter and unchained steps", async (ctx) => {
+  await addSwapAdapter(ctx, XEXCHANGE_ROUTER);
+  await createStreamAndSettle(ctx);
+
+  await claimFromStreamSwapWithAdapter(ctx, 1, [
+    generateSwapStep(ctx, ctx.payment_esdt_token_identifier, "WEGLD-bd4d79", 500),
+    generateSwapStep(ctx, "MEX-455c57", "USDC-c76f1f", 0),
+  ]).assertFail({ message: ERR_SWAP_ADAPTER_INVALID_STEPS });
+});
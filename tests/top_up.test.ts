@@ -0,0 +1,132 @@
+import { expect, test } from "vitest";
+import { e } from "xsuite";
+
+import { ERR_CANT_TOP_UP, ERR_INVALID_TOP_UP_TOKEN, ERR_TOP_UP_ONLY_SENDER } from "./errors";
+import {
+  cancelStream,
+  claimFromStream,
+  createStream,
+  generateStreamNftAttr,
+  generateStreamSegment,
+  getStream,
+  requireEgldBalance,
+  requireValidStreamNft,
+} from "./utils";
+
+const topUpStream = (ctx, streamId: number, amount: number, duration: number) => {
+  return ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "topUpStream",
+    funcArgs: [e.U64(streamId), e.List(generateStreamSegment(amount, 1, duration))],
+    value: amount,
+  });
+};
+
+test("Top up stream", async (ctx) => {
+  const streamId = await createStream(ctx, 600, 0);
+
+  await topUpStream(ctx, streamId, 10, 400);
+
+  const stream = await getStream(ctx, streamId);
+  expect(stream.deposit).toBe(20n);
+  expect(stream.end_time).toBe(1000n);
+  expect(stream.segments.length).toBe(2);
+
+  await ctx.world.setCurrentBlockInfo({
+    timestamp: 800,
+  });
+
+  await claimFromStream(ctx, streamId);
+
+  await requireEgldBalance(ctx, ctx.recipient_wallet, 15);
+  await requireValidStreamNft(ctx);
+});
+
+test("Top up stream with NFT in custody", async (ctx) => {
+  const streamId = await createStream(ctx, 600, 0);
+
+  await ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "depositStreamNft",
+    funcArgs: [e.U64(streamId)],
+    value: 0,
+    esdts: [{ id: ctx.stream_nft_token_identifier, nonce: streamId, amount: 1 }],
+  });
+
+  await topUpStream(ctx, streamId, 10, 400);
+
+  // The attributes of the NFT held by the contract are refreshed right away
+  const stream = await getStream(ctx, streamId);
+  expect(stream.deposit).toBe(20n);
+  const key = e.Str(`ELRONDesdt${ctx.stream_nft_token_identifier}`).toTopHex() + e.U64(streamId).toTopHex();
+  const value = (await ctx.world.sysAcc.getAccountKvs())[key];
+  expect(value).toContain(generateStreamNftAttr(stream).toTopHex());
+});
+
+test("Top up stream with protocol fee", async (ctx) => {
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setProtocolFee",
+    funcArgs: [e.Str("EGLD"), e.U(10_00n)],
+    value: 0,
+  });
+
+  const streamId = await createStream(ctx, 600, 0);
+
+  await ctx.sender_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "topUpStream",
+    funcArgs: [e.U64(streamId), e.List(generateStreamSegment(9, 1, 400))],
+    value: 10,
+  });
+
+  const stream = await getStream(ctx, streamId);
+  expect(stream.deposit).toBe(18n);
+});
+
+test("Top up stream not sender", async (ctx) => {
+  const streamId = await createStream(ctx, 600, 0);
+
+  await ctx.recipient_wallet
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 50_000_000,
+      funcName: "topUpStream",
+      funcArgs: [e.U64(streamId), e.List(generateStreamSegment(10, 1, 400))],
+      value: 0,
+    })
+    .assertFail({ message: ERR_TOP_UP_ONLY_SENDER });
+});
+
+test("Top up stream with different token", async (ctx) => {
+  const streamId = await createStream(ctx, 600, 0);
+
+  await ctx.sender_wallet
+    .callContract({
+      callee: ctx.contract,
+      gasLimit: 50_000_000,
+      funcName: "topUpStream",
+      funcArgs: [e.U64(streamId), e.List(generateStreamSegment(10, 1, 400))],
+      value: 0,
+      esdts: [
+        {
+          id: ctx.payment_esdt_token_identifier,
+          nonce: 0,
+          amount: 10,
+        },
+      ],
+    })
+    .assertFail({ message: ERR_INVALID_TOP_UP_TOKEN });
+});
+
+test("Top up canceled stream", async (ctx) => {
+  const streamId = await createStream(ctx, 600, 0);
+
+  await cancelStream(ctx, streamId, true, false);
+
+  await topUpStream(ctx, streamId, 10, 400).assertFail({ message: ERR_CANT_TOP_UP });
+});
@@ -68,6 +68,27 @@ export const claimFromStream = (ctx: TestContext, streamId: number): TxResultPro
   });
 };
 
+export const claimFromStreamAmount = (
+  ctx: TestContext,
+  streamId: number,
+  amount: number
+): TxResultPromise<CallContractTxResult> => {
+  return ctx.recipient_wallet.callContract({
+    callee: ctx.contract,
+    gasLimit: 50_000_000,
+    funcName: "claimFromStreamAmount",
+    funcArgs: [e.U64(streamId), e.U(amount)],
+    value: 0,
+    esdts: [
+      {
+        id: ctx.stream_nft_token_identifier,
+        nonce: streamId,
+        amount: 1,
+      },
+    ],
+  });
+};
+
 export const cancelStream = (
   ctx: TestContext,
   streamId: number,
@@ -126,6 +147,7 @@ export const requireStreamInvalid = async (ctx: TestContext, streamId: number) =
 const segmentDecoder = d.Tuple({
   amount: d.U(),
   exponent: d.U32(),
+  exponent_denominator: d.U32(),
   duration: d.U64(),
 });
 
@@ -145,8 +167,10 @@ export const streamDecoder = d.Tuple({
   start_time: d.U64(),
   end_time: d.U64(),
   cliff: d.U64(),
+  cliff_amount: d.U(),
   segments: d.List(segmentDecoder),
   balances_after_cancel: d.Option(balancesAfterCancelDecoder),
+  acceptance_deadline: d.Option(d.U64()),
 });
 
 export const generateStreamNftAttr = (stream: any) => {
@@ -160,12 +184,18 @@ export const generateStreamNftAttr = (stream: any) => {
     e.U64(stream.start_time),
     e.U64(stream.end_time),
     e.U64(stream.cliff),
+    e.U(stream.cliff_amount),
     e.Bool(false)
   );
 };
 
-export const generateStreamSegment = (amount: number | bigint, exponent: number, duration: number) => {
-  return e.Tuple(e.U(amount), e.U32(exponent), e.U64(duration));
+export const generateStreamSegment = (
+  amount: number | bigint,
+  exponent: number,
+  duration: number,
+  exponentDenominator = 1
+) => {
+  return e.Tuple(e.U(amount), e.U32(exponent), e.U32(exponentDenominator), e.U64(duration));
 };
 
 export const generateSegmentsWithValue = (count: number, amount: bigint) => {
@@ -177,3 +207,44 @@ export const generateSegmentsWithValue = (count: number, amount: bigint) => {
 
   return e.List(...segments);
 };
+
+export const deployAshAggregatorMock = async (ctx: TestContext, outputToken: string, outputAmount: number) => {
+  const aggregator = await ctx.world.createContract({
+    code: "file:mocks/ash-aggregator/output/ash-aggregator-mock.wasm",
+    kvs: [
+      e.kvs.Mapper("outputAmount").Value(e.U(outputAmount)),
+      e.kvs.Esdts([{ id: outputToken, amount: outputAmount }]),
+    ],
+  });
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setAshAggregatorSc",
+    funcArgs: [aggregator],
+    value: 0,
+  });
+
+  return aggregator;
+};
+
+export const deployWrapEgldMock = async (ctx: TestContext, wrappedEgldToken: string, amount: number) => {
+  const wrapEgld = await ctx.world.createContract({
+    code: "file:mocks/wrap-egld/output/wrap-egld-mock.wasm",
+    balance: amount,
+    kvs: [
+      e.kvs.Mapper("wrappedEgldTokenId").Value(e.Str(wrappedEgldToken)),
+      e.kvs.Esdts([{ id: wrappedEgldToken, amount }]),
+    ],
+  });
+
+  await ctx.deployer.callContract({
+    callee: ctx.contract,
+    gasLimit: 10_000_000,
+    funcName: "setWrapEgld",
+    funcArgs: [wrapEgld, e.Str(wrappedEgldToken)],
+    value: 0,
+  });
+
+  return wrapEgld;
+};
@@ -5,9 +5,9 @@
 ////////////////////////////////////////////////////
 
 // Init:                                 1
-// Endpoints:                           19
+// Endpoints:                           88
 // Async Callback:                       1
-// Total number of exported functions:  21
+// Total number of exported functions:  90
 
 #![no_std]
 
@@ -23,24 +23,93 @@ multiversx_sc_wasm_adapter::endpoints! {
     (
         init => init
         getStreamData => get_stream
+        getLastStreamId => get_last_stream_id
+        getStreamIdByNftNonce => get_stream_id_by_nft_nonce
+        getStreamSecretHash => stream_secret_hash
+        getRedeemCommitment => redeem_commitment
+        getClaimDestination => claim_destination
+        getStreamHolder => stream_holder
+        getStreamClaimOperators => stream_claim_operators
         getProtocolFee => protocol_fee
+        getPendingProtocolFee => pending_protocol_fee
+        getProtocolFeeDelay => protocol_fee_delay
+        getPendingProtocolFeeDelay => pending_protocol_fee_delay
+        getAccruedProtocolFees => get_accrued_protocol_fees
+        getAccruedProtocolFee => accrued_protocol_fee
+        getKeeperFee => keeper_fee
+        getStreamProtocolFee => stream_protocol_fee
+        getProtocolFeeTreasury => protocol_fee_treasury
+        isBrokerRegistryEnabled => broker_registry_enabled
+        getBrokerConfig => broker_config
+        getBrokerFeeVolume => broker_fee_volume
         getAshAggregatorSc => ash_aggregator_sc
         getWrapEgldSc => wrap_egld_sc
         getWrapEgldToken => wrap_egld_token
+        getLastSwapAdapterId => last_swap_adapter_id
+        getSwapAdapter => swap_adapter
+        getWhitelistedCallEndpoints => whitelisted_call_endpoints
+        getCallReturnTokens => call_return_tokens
         createStreamNow => create_stream_now
         createStream => create_stream
+        createStreams => create_streams
+        createStreamSwap => create_stream_swap
+        createHashLockedStream => create_hash_locked_stream
+        quoteCreateStream => quote_create_stream
         streamedAmount => streamed_amount
         recipientBalance => recipient_balance
         senderBalance => sender_balance
         claimFromStream => claim_from_stream
+        claimFromStreamAmount => claim_from_stream_amount
+        claimFromStreamTo => claim_from_stream_to
+        claimFromStreamAndCall => claim_from_stream_and_call
+        setClaimDestination => set_claim_destination
+        claimFromStreams => claim_from_streams
         claimFromStreamSwap => claim_from_stream_swap
+        claimFromStreamSwapWithAdapter => claim_from_stream_swap_with_adapter
+        claimFromStreamSwapAsync => claim_from_stream_swap_async
         cancelStream => cancel_stream
         claimFromStreamAfterCancel => claim_from_stream_after_cancel
         renounceCancelStream => renounce_cancel_stream
         setProtocolFee => set_protocol_fee
         removeProtocolFee => remove_protocol_fee
+        announceProtocolFee => announce_protocol_fee
+        applyProtocolFee => apply_protocol_fee
+        cancelProtocolFee => cancel_protocol_fee
+        setProtocolFeeDelay => set_protocol_fee_delay
+        announceProtocolFeeDelay => announce_protocol_fee_delay
+        applyProtocolFeeDelay => apply_protocol_fee_delay
+        cancelProtocolFeeDelay => cancel_protocol_fee_delay
+        setBroker => set_broker
+        setBrokerEnabled => set_broker_enabled
+        removeBroker => remove_broker
+        setBrokerRegistryEnabled => set_broker_registry_enabled
+        setProtocolFeeTreasury => set_protocol_fee_treasury
+        withdrawProtocolFees => withdraw_protocol_fees
+        setKeeperFee => set_keeper_fee
+        addSwapAdapter => add_swap_adapter
+        updateSwapAdapter => update_swap_adapter
+        removeSwapAdapter => remove_swap_adapter
+        whitelistCallEndpoint => whitelist_call_endpoint
+        setCallReturnTokens => set_call_return_tokens
+        removeCallEndpoint => remove_call_endpoint
+        setAshAggregatorSc => set_ash_aggregator_sc
+        setWrapEgld => set_wrap_egld
         getStatusOf => status_of
         issueToken => issue_token
+        topUpStream => top_up_stream
+        acceptStream => accept_stream
+        commitRedeem => commit_redeem
+        redeemStream => redeem_stream
+        reclaimStream => reclaim_stream
+        clearRedeemCommitments => clear_redeem_commitments
+        depositStreamNft => deposit_stream_nft
+        withdrawStreamNft => withdraw_stream_nft
+        addClaimOperator => add_claim_operator
+        revokeClaimOperator => revoke_claim_operator
+        claimFromStreamByOperator => claim_from_stream_by_operator
+        settleStream => settle_stream
+        restream => restream
+        splitStream => split_stream
     )
 }
 